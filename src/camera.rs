@@ -1,6 +1,6 @@
-use glam::{Mat4, Quat, Vec3};
+use glam::{Mat4, Quat, Vec3, Vec4};
 
-use crate::optics::Lens;
+use crate::optics::{frustum_planes_from_clip, Lens};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub struct CameraDescriptor<L> {
@@ -59,6 +59,14 @@ where
     pub fn world_to_clip(&self) -> Mat4 {
         self.lens.camera_to_clip() * self.world_to_camera()
     }
+
+    /// The six world-space frustum planes, in order
+    /// `[left, right, bottom, top, near, far]`, normalized so that
+    /// `plane.dot(p.extend(1.0))` gives the signed distance from a
+    /// world-space point `p` to the plane.
+    pub fn frustum_planes(&self) -> [Vec4; 6] {
+        frustum_planes_from_clip(self.world_to_clip())
+    }
 }
 
 impl<L> From<CameraDescriptor<L>> for Camera<L>
@@ -0,0 +1,93 @@
+use bytemuck::Zeroable;
+use empa::buffer::{Buffer, Uniform};
+use empa::device::Device;
+use empa::resource_binding::{BindGroup, BindGroupLayout};
+use empa::type_flag::{O, X};
+use empa::{abi, buffer};
+use empa_glam::ToAbi;
+
+use crate::camera::Camera;
+use crate::optics::Lens;
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+struct Uniforms {
+    world_to_camera: abi::Mat4x4,
+    camera_to_world: abi::Mat4x4,
+    camera_to_clip: abi::Mat4x4,
+    world_to_clip: abi::Mat4x4,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE | VERTEX | FRAGMENT")]
+    uniforms: Uniform<'a, Uniforms>,
+}
+
+pub type CameraBindGroupLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+/// A single reusable uniform buffer and bind group holding the camera's
+/// `world_to_camera`, `camera_to_world`, `camera_to_clip` and `world_to_clip`
+/// matrices. Passes that need the camera take `&CameraBinding` and bind it
+/// at a fixed group index instead of each allocating and populating their
+/// own uniform buffer per dispatch; call `update` once per frame before
+/// encoding any of them.
+pub struct CameraBinding {
+    device: Device,
+    uniforms: Buffer<Uniforms, buffer::Usages<O, O, O, X, O, O, X, O, O, O>>,
+    bind_group_layout: BindGroupLayout<CameraBindGroupLayout>,
+    bind_group: BindGroup<CameraBindGroupLayout>,
+}
+
+impl CameraBinding {
+    pub fn init(device: Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout::<CameraBindGroupLayout>();
+
+        let uniforms = device.create_buffer(
+            Uniforms::zeroed(),
+            buffer::Usages::uniform_binding().and_copy_dst(),
+        );
+
+        let bind_group = device.create_bind_group(
+            &bind_group_layout,
+            Resources {
+                uniforms: uniforms.uniform(),
+            },
+        );
+
+        CameraBinding {
+            device,
+            uniforms,
+            bind_group_layout,
+            bind_group,
+        }
+    }
+
+    pub fn bind_group_layout(&self) -> &BindGroupLayout<CameraBindGroupLayout> {
+        &self.bind_group_layout
+    }
+
+    pub fn bind_group(&self) -> &BindGroup<CameraBindGroupLayout> {
+        &self.bind_group
+    }
+
+    /// Uploads `camera`'s matrices to the shared uniform buffer. Call once
+    /// per frame, before encoding any pass that binds this group.
+    pub fn update<L>(&self, camera: &Camera<L>)
+    where
+        L: Lens,
+    {
+        let world_to_camera = camera.world_to_camera();
+        let camera_to_clip = camera.lens().camera_to_clip();
+        let world_to_clip = camera_to_clip * world_to_camera;
+
+        self.device.queue().write_buffer(
+            self.uniforms.view(),
+            &Uniforms {
+                world_to_camera: world_to_camera.to_abi(),
+                camera_to_world: world_to_camera.inverse().to_abi(),
+                camera_to_clip: camera_to_clip.to_abi(),
+                world_to_clip: world_to_clip.to_abi(),
+            },
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::f32::consts::PI;
 use std::rc::Rc;
 
@@ -12,9 +12,13 @@ use futures::{future, FutureExt, StreamExt};
 use glam::{Quat, Vec3};
 
 use crate::camera::Camera;
+use crate::keyboard_tracker::KeyboardTracker;
 use crate::mouse_movement_tracker::MouseMovementTracker;
 use crate::optics::Lens;
 
+const FLY_SPEED: f32 = 2.0;
+const VIEWPOINT_TRANSITION_DURATION: f32 = 0.6;
+
 pub struct CameraController {
     data: Rc<RefCell<ControllerData>>,
 }
@@ -32,6 +36,20 @@ impl CameraController {
             },
             mouse_wheel_delta: 0.0,
             mouse_tracking_session: None,
+            viewpoints: Vec::new(),
+            active_viewpoint: None,
+            free_look_transform: None,
+            transition: None,
+        }));
+
+        let data_clone = data.clone();
+
+        spawn_local(window().on_key_down().for_each(move |event| {
+            if event.code() == "KeyC" {
+                cycle_viewpoint(&data_clone);
+            }
+
+            future::ready(())
         }));
 
         let data_clone = data.clone();
@@ -103,6 +121,17 @@ impl CameraController {
                                 },
                             ));
                         }
+                        PointerButton::Primary => {
+                            data.mouse_tracking_session = Some(
+                                FlySession::init(&canvas_clone, data.camera_transform).into(),
+                            );
+
+                            spawn_local(canvas_clone.on_pointer_up().into_future().map(
+                                move |_| {
+                                    data_clone_clone.borrow_mut().mouse_tracking_session = None;
+                                },
+                            ));
+                        }
                         _ => (),
                     }
 
@@ -113,14 +142,60 @@ impl CameraController {
         CameraController { data }
     }
 
-    pub fn update_camera<L>(&self, camera: &mut Camera<L>)
+    /// Registers a named camera bookmark that `set_active_viewpoint` and the
+    /// `C` cycling hotkey can switch to.
+    pub fn add_viewpoint(&self, name: impl Into<String>, transform: CameraTransform) {
+        self.data
+            .borrow_mut()
+            .viewpoints
+            .push((name.into(), transform));
+    }
+
+    /// Smoothly transitions the live camera to the viewpoint at `index`.
+    pub fn set_active_viewpoint(&self, index: usize) {
+        let mut data = self.data.borrow_mut();
+
+        if index >= data.viewpoints.len() {
+            return;
+        }
+
+        let from = data.camera_transform;
+
+        if data.active_viewpoint.is_none() {
+            data.free_look_transform = Some(from);
+        }
+
+        data.active_viewpoint = Some(index);
+        data.transition = Some(Transition {
+            from,
+            to: data.viewpoints[index].1,
+            elapsed: 0.0,
+            duration: VIEWPOINT_TRANSITION_DURATION,
+        });
+    }
+
+    pub fn update_camera<L>(&self, camera: &mut Camera<L>, dt: f32)
     where
         L: Lens,
     {
         let mut data = self.data.borrow_mut();
 
-        if let Some(session) = &data.mouse_tracking_session {
-            data.camera_transform = session.current_transform();
+        if let Some(mut transition) = data.transition.take() {
+            transition.elapsed += dt;
+
+            let t = (transition.elapsed / transition.duration).clamp(0.0, 1.0);
+
+            data.camera_transform = CameraTransform {
+                position: transition.from.position.lerp(transition.to.position, t),
+                orientation: transition.from.orientation.slerp(transition.to.orientation, t),
+                orbit_point: transition.to.orbit_point,
+            };
+
+            if t < 1.0 {
+                data.transition = Some(transition);
+            }
+        } else if let Some(session) = &data.mouse_tracking_session {
+            data.camera_transform = session.current_transform(dt);
         } else {
             let camera_transform = data.camera_transform;
             let difference = camera_transform.orbit_point - camera_transform.position;
@@ -141,13 +216,23 @@ impl CameraController {
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
-struct CameraTransform {
+pub struct CameraTransform {
     position: Vec3,
     orientation: Quat,
     orbit_point: Vec3,
 }
 
 impl CameraTransform {
+    /// Constructs a transform suitable for a saved viewpoint, with the
+    /// orbit point initialized to the position itself.
+    pub fn new(position: Vec3, orientation: Quat) -> Self {
+        CameraTransform {
+            position,
+            orientation,
+            orbit_point: position,
+        }
+    }
+
     fn up(&self) -> Vec3 {
         self.orientation * Vec3::new(0.0, 1.0, 0.0)
     }
@@ -155,24 +240,76 @@ impl CameraTransform {
     fn right(&self) -> Vec3 {
         self.orientation * Vec3::new(1.0, 0.0, 0.0)
     }
+
+    fn forward(&self) -> Vec3 {
+        self.orientation * Vec3::new(0.0, 0.0, -1.0)
+    }
 }
 
 struct ControllerData {
     camera_transform: CameraTransform,
     mouse_wheel_delta: f32,
     mouse_tracking_session: Option<MouseTrackingSession>,
+    viewpoints: Vec<(String, CameraTransform)>,
+    active_viewpoint: Option<usize>,
+    free_look_transform: Option<CameraTransform>,
+    transition: Option<Transition>,
+}
+
+struct Transition {
+    from: CameraTransform,
+    to: CameraTransform,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// Advances to the next saved viewpoint, wrapping back to the free-look
+/// transform that was active before the first viewpoint was selected.
+fn cycle_viewpoint(data: &Rc<RefCell<ControllerData>>) {
+    let mut data = data.borrow_mut();
+
+    if data.viewpoints.is_empty() {
+        return;
+    }
+
+    let from = data.camera_transform;
+
+    let next_viewpoint = match data.active_viewpoint {
+        None => Some(0),
+        Some(index) if index + 1 < data.viewpoints.len() => Some(index + 1),
+        Some(_) => None,
+    };
+
+    if data.active_viewpoint.is_none() {
+        data.free_look_transform = Some(from);
+    }
+
+    let to = match next_viewpoint {
+        Some(index) => data.viewpoints[index].1,
+        None => data.free_look_transform.unwrap_or(from),
+    };
+
+    data.active_viewpoint = next_viewpoint;
+    data.transition = Some(Transition {
+        from,
+        to,
+        elapsed: 0.0,
+        duration: VIEWPOINT_TRANSITION_DURATION,
+    });
 }
 
 enum MouseTrackingSession {
     Orbit(OrbitSession),
     Sidle(SidleSession),
+    Fly(FlySession),
 }
 
 impl MouseTrackingSession {
-    fn current_transform(&self) -> CameraTransform {
+    fn current_transform(&self, dt: f32) -> CameraTransform {
         match self {
             MouseTrackingSession::Orbit(session) => session.current_transform(),
             MouseTrackingSession::Sidle(session) => session.current_transform(),
+            MouseTrackingSession::Fly(session) => session.current_transform(dt),
         }
     }
 }
@@ -189,6 +326,12 @@ impl From<SidleSession> for MouseTrackingSession {
     }
 }
 
+impl From<FlySession> for MouseTrackingSession {
+    fn from(session: FlySession) -> Self {
+        MouseTrackingSession::Fly(session)
+    }
+}
+
 struct OrbitSession {
     tracker: MouseMovementTracker,
     orbit_point: Vec3,
@@ -275,3 +418,88 @@ impl SidleSession {
         }
     }
 }
+
+struct FlySession {
+    mouse_tracker: MouseMovementTracker,
+    keyboard_tracker: KeyboardTracker,
+    initial_orientation: Quat,
+    position: Cell<Vec3>,
+}
+
+impl FlySession {
+    fn init(canvas_element: &HtmlCanvasElement, initial_transform: CameraTransform) -> Self {
+        FlySession {
+            mouse_tracker: MouseMovementTracker::pointer_locked(canvas_element),
+            keyboard_tracker: KeyboardTracker::new(),
+            initial_orientation: initial_transform.orientation,
+            position: Cell::new(initial_transform.position),
+        }
+    }
+
+    fn current_transform(&self, dt: f32) -> CameraTransform {
+        let mouse_movement = self.mouse_tracker.movement();
+
+        let pan_angle = -mouse_movement.x as f32 / 400.0 * PI;
+        let pan = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), pan_angle);
+
+        let panned_orientation = pan * self.initial_orientation;
+        let right = panned_orientation * Vec3::new(1.0, 0.0, 0.0);
+
+        let tilt_angle = -mouse_movement.y as f32 / 400.0 * PI;
+        let tilt = Quat::from_axis_angle(right, tilt_angle);
+
+        let orientation = tilt * pan * self.initial_orientation;
+
+        let transform = CameraTransform {
+            position: self.position.get(),
+            orientation,
+            orbit_point: self.position.get(),
+        };
+
+        let mut translation = Vec3::ZERO;
+
+        if self.keyboard_tracker.is_held("KeyW") {
+            translation += transform.forward();
+        }
+
+        if self.keyboard_tracker.is_held("KeyS") {
+            translation -= transform.forward();
+        }
+
+        if self.keyboard_tracker.is_held("KeyD") {
+            translation += transform.right();
+        }
+
+        if self.keyboard_tracker.is_held("KeyA") {
+            translation -= transform.right();
+        }
+
+        if self.keyboard_tracker.is_held("Space") {
+            translation += transform.up();
+        }
+
+        if self.keyboard_tracker.is_held("ControlLeft") {
+            translation -= transform.up();
+        }
+
+        if translation != Vec3::ZERO {
+            translation = translation.normalize() * FLY_SPEED * dt;
+        }
+
+        let position = self.position.get() + translation;
+
+        self.position.set(position);
+
+        CameraTransform {
+            position,
+            orientation,
+            orbit_point: position,
+        }
+    }
+}
+
+impl Drop for FlySession {
+    fn drop(&mut self) {
+        window().document().exit_pointer_lock();
+    }
+}
@@ -6,4 +6,38 @@ use empa::abi;
 pub struct Circle {
     pub origin: abi::Vec2<f32>,
     pub radius: f32,
+    pub color: abi::Vec4<f32>,
+    pub z_index: i32,
+}
+
+impl Circle {
+    pub fn contains_point(&self, point: abi::Vec2<f32>) -> bool {
+        let dx = point.x - self.origin.x;
+        let dy = point.y - self.origin.y;
+
+        dx * dx + dy * dy <= self.radius * self.radius
+    }
+
+    pub fn intersects_circle(&self, other: &Circle) -> bool {
+        let dx = other.origin.x - self.origin.x;
+        let dy = other.origin.y - self.origin.y;
+        let radii = self.radius + other.radius;
+
+        dx * dx + dy * dy <= radii * radii
+    }
+
+    /// Tests this circle against an axis-aligned rectangle (`min`/`max`
+    /// corners) by clamping the circle's origin into the rectangle and
+    /// checking the distance to that closest point, so it also catches the
+    /// case where the circle overlaps an edge or corner without its origin
+    /// falling inside the rectangle.
+    pub fn in_rectangle(&self, min: abi::Vec2<f32>, max: abi::Vec2<f32>) -> bool {
+        let closest_x = self.origin.x.clamp(min.x, max.x);
+        let closest_y = self.origin.y.clamp(min.y, max.y);
+
+        let dx = self.origin.x - closest_x;
+        let dy = self.origin.y - closest_y;
+
+        dx * dx + dy * dy <= self.radius * self.radius
+    }
 }
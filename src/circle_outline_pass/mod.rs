@@ -0,0 +1,184 @@
+use std::f32::consts::PI;
+
+use bytemuck::Zeroable;
+use empa::buffer::{Buffer, BufferUsages, Storage, Uniform};
+use empa::command::{
+    DrawIndexed, DrawIndexedCommandEncoder, RenderBundleEncoderDescriptor, RenderStateEncoder,
+    ResourceBindingCommandEncoder,
+};
+use empa::device::Device;
+use empa::render_pipeline::{
+    ColorOutput, ColorWrite, DepthStencilTest, FragmentStageBuilder, IndexAny, PrimitiveAssembly,
+    RenderPipeline, RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::texture::format::depth24plus;
+use empa::{abi, buffer};
+
+use crate::circle::Circle;
+use crate::renderer::{MainColorFormat, MainPassBundle, MainPassLayout, MAIN_COLOR_FORMAT};
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+struct CircleOutlineData {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+}
+
+impl CircleOutlineData {
+    pub fn new(subdivisions: usize, outline_width: f32) -> Self {
+        let inner_radius = 1.0 - outline_width;
+        let segment_angle = (2.0 * PI) / subdivisions as f32;
+
+        let mut vertices = Vec::with_capacity(2 * subdivisions);
+        let mut indices = Vec::with_capacity(2 * subdivisions + 2);
+
+        for i in 0..subdivisions {
+            let angle = i as f32 * segment_angle;
+            let direction = [f32::cos(angle), f32::sin(angle)];
+
+            vertices.push(Vertex {
+                position: [direction[0] * inner_radius, direction[1] * inner_radius],
+            });
+            vertices.push(Vertex {
+                position: direction,
+            });
+
+            indices.push((2 * i) as u32);
+            indices.push((2 * i + 1) as u32);
+        }
+
+        // Close the ring by revisiting the first pair of vertices.
+        indices.push(0);
+        indices.push(1);
+
+        CircleOutlineData { vertices, indices }
+    }
+}
+
+#[derive(empa::render_pipeline::Vertex, Clone, Copy, Debug)]
+#[repr(C)]
+struct Vertex {
+    #[vertex_attribute(location = 0, format = "float32x2")]
+    position: [f32; 2],
+}
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+struct Uniforms {
+    color: abi::Vec4<f32>,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "VERTEX")]
+    circles: Storage<'a, [Circle]>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    uniforms: Uniform<'a, Uniforms>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+/// Renders just the ring/boundary of each [`Circle`], as a companion to
+/// [`crate::occluder_circles_pass::OccluderCirclesPass`]'s filled rendering,
+/// so debug views can overlay bounding outlines without covering the scene.
+///
+/// Not yet invoked from `Renderer`/`main`'s frame loop — see
+/// [`crate::hi_z_pass::HiZPass`]'s doc comment for what's blocking the
+/// end-to-end occlusion-culling pipeline this is a debug companion to.
+pub struct CircleOutlinePass {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: RenderPipeline<MainPassLayout, Vertex, IndexAny, (ResourcesLayout,)>,
+    vertices: Buffer<[Vertex], BufferUsages!(Vertex)>,
+    indices: Buffer<[u32], BufferUsages!(Index)>,
+}
+
+impl CircleOutlinePass {
+    pub async fn init(device: Device, subdivisions: usize, outline_width: f32) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_render_pipeline(
+                &RenderPipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .primitive_assembly(PrimitiveAssembly::triangle_strip())
+                    .vertex(
+                        VertexStageBuilder::begin(&shader, "vert_main")
+                            .vertex_layout::<Vertex>()
+                            .finish(),
+                    )
+                    .fragment(
+                        FragmentStageBuilder::begin(&shader, "frag_main")
+                            .color_outputs(ColorOutput {
+                                format: MAIN_COLOR_FORMAT,
+                                write_mask: ColorWrite::All,
+                            })
+                            .finish(),
+                    )
+                    .depth_stencil_test(DepthStencilTest::read_write::<depth24plus>())
+                    .finish(),
+            )
+            .await;
+
+        let CircleOutlineData { vertices, indices } =
+            CircleOutlineData::new(subdivisions, outline_width);
+
+        let vertices = device.create_buffer(vertices, buffer::Usages::vertex());
+        let indices = device.create_buffer(indices, buffer::Usages::index());
+
+        CircleOutlinePass {
+            device,
+            bind_group_layout,
+            pipeline,
+            vertices,
+            indices,
+        }
+    }
+
+    pub fn render_bundle(
+        &self,
+        circles: buffer::View<[Circle], impl buffer::StorageBinding>,
+        color: abi::Vec4<f32>,
+    ) -> Option<MainPassBundle> {
+        if circles.len() == 0 {
+            return None;
+        }
+
+        let uniforms = self
+            .device
+            .create_buffer(Uniforms { color }, buffer::Usages::uniform_binding());
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                circles: circles.storage(),
+                uniforms: uniforms.uniform(),
+            },
+        );
+
+        let render_bundle_encoder = self.device.create_render_bundle_encoder(
+            &RenderBundleEncoderDescriptor::new::<MainColorFormat>()
+                .depth_stencil_format::<depth24plus>(),
+        );
+
+        let bundle = render_bundle_encoder
+            .set_pipeline(&self.pipeline)
+            .set_vertex_buffers(&self.vertices)
+            .set_index_buffer(&self.indices)
+            .set_bind_groups(&bind_group)
+            .draw_indexed(DrawIndexed {
+                index_count: self.indices.len() as u32,
+                instance_count: circles.len() as u32,
+                first_index: 0,
+                base_vertex: 0,
+                first_instance: 0,
+            })
+            .finish();
+
+        Some(bundle)
+    }
+}
@@ -0,0 +1,189 @@
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Buffer, Storage, StorageBinding};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::buffer;
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::type_flag::{O, X};
+
+use crate::circle::Circle;
+use crate::cull_spheres_pass::DrawIndexedIndirectArgs;
+
+const GROUP_SIZE: u32 = 256;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    circles: Storage<'a, [Circle]>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    visibility: Storage<'a, [u32]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    compacted_circles: Storage<'a, [Circle], ReadWrite>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    indirect_args: Storage<'a, DrawIndexedIndirectArgs, ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+type CirclesBufferUsages = buffer::Usages<O, O, O, O, O, O, O, X, O, O>;
+type IndirectArgsBufferUsages = buffer::Usages<O, O, O, X, O, O, O, X, X, O>;
+
+/// Owns a persistent, device-side destination for [`CompactOccluderCirclesPass`]:
+/// a `circles` buffer sized to the largest capacity requested so far (grown
+/// to the next power of two rather than reallocated on every call) plus the
+/// [`DrawIndexedIndirectArgs`] buffer that doubles as the atomic compaction
+/// counter and the `draw_indexed_indirect` arguments. Only the first
+/// `instance_count` circles (as written into `indirect_args` by the GPU) are
+/// ever drawn, so growing `circles` ahead of actual demand is harmless.
+pub struct CompactedOccluderCircles {
+    circles: Buffer<[Circle], CirclesBufferUsages>,
+    indirect_args: Buffer<DrawIndexedIndirectArgs, IndirectArgsBufferUsages>,
+    capacity: usize,
+}
+
+impl CompactedOccluderCircles {
+    pub fn new(device: &Device, index_count: u32, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+
+        CompactedOccluderCircles {
+            circles: device.create_slice_buffer_zeroed(capacity, buffer::Usages::storage_binding()),
+            indirect_args: device.create_buffer(
+                DrawIndexedIndirectArgs {
+                    index_count,
+                    instance_count: 0,
+                    first_index: 0,
+                    base_vertex: 0,
+                    first_instance: 0,
+                },
+                buffer::Usages::storage_binding()
+                    .and_indirect()
+                    .and_copy_dst(),
+            ),
+            capacity,
+        }
+    }
+
+    /// Grows the backing `circles` buffer (to `required` rounded up to the
+    /// next power of two) if `required` exceeds its current capacity. The `indirect_args` buffer never needs to grow,
+    /// since it always holds exactly one `DrawIndexedIndirectArgs` value.
+    pub fn ensure_capacity(&mut self, device: &Device, index_count: u32, required: usize) {
+        if required > self.capacity {
+            let capacity = required.next_power_of_two();
+
+            self.circles =
+                device.create_slice_buffer_zeroed(capacity, buffer::Usages::storage_binding());
+            self.indirect_args = device.create_buffer(
+                DrawIndexedIndirectArgs {
+                    index_count,
+                    instance_count: 0,
+                    first_index: 0,
+                    base_vertex: 0,
+                    first_instance: 0,
+                },
+                buffer::Usages::storage_binding()
+                    .and_indirect()
+                    .and_copy_dst(),
+            );
+            self.capacity = capacity;
+        }
+    }
+
+    pub fn circles(&self) -> buffer::View<[Circle], CirclesBufferUsages> {
+        self.circles.view()
+    }
+
+    pub fn indirect_args(&self) -> buffer::View<DrawIndexedIndirectArgs, IndirectArgsBufferUsages> {
+        self.indirect_args.view()
+    }
+}
+
+/// Compacts the [`Circle`]s marked visible in a flat per-instance `visibility`
+/// buffer (such as the one produced by [`crate::hi_z_pass::HiZPass::cull`])
+/// into a dense prefix of a [`CompactedOccluderCircles`] destination, and
+/// writes the resulting count into its `indirect_args`, so
+/// `OccluderCirclesPass::render_bundle_indirect` only ever draws visible
+/// occluders.
+///
+/// Not yet invoked from `Renderer`/`main`'s frame loop — see
+/// [`crate::hi_z_pass::HiZPass`]'s doc comment for what's blocking the
+/// end-to-end occlusion-culling pipeline this pass is part of.
+pub struct CompactOccluderCirclesPass {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+}
+
+impl CompactOccluderCirclesPass {
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        CompactOccluderCirclesPass {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn encode(
+        &self,
+        encoder: CommandEncoder,
+        circles: buffer::View<[Circle], impl StorageBinding>,
+        visibility: buffer::View<[u32], impl StorageBinding>,
+        destination: &CompactedOccluderCircles,
+        index_count: u32,
+    ) -> CommandEncoder {
+        // Reset the atomic instance counter before the shader compacts
+        // visible circles and increments it.
+        self.device.queue().write_buffer(
+            destination.indirect_args(),
+            &DrawIndexedIndirectArgs {
+                index_count,
+                instance_count: 0,
+                first_index: 0,
+                base_vertex: 0,
+                first_instance: 0,
+            },
+        );
+
+        let workgroups = (circles.len() as u32).div_ceil(GROUP_SIZE);
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                circles: circles.storage(),
+                visibility: visibility.storage(),
+                compacted_circles: destination.circles().storage(),
+                indirect_args: destination.indirect_args().storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
@@ -42,6 +42,13 @@ pub struct ComputeOccluderCirclesPassInput<'a, U0, U1> {
     pub occluder_circles: buffer::View<'a, [Circle], U1>,
 }
 
+/// Projects each `Sphere` into a conservative screen-space bounding `Circle`
+/// using the tight perspective tangent-line bound (Mara-McGuire), suitable
+/// for feeding straight into [`crate::occluder_circles_pass::OccluderCirclesPass`].
+///
+/// Not yet invoked from `Renderer`/`main`'s frame loop — see
+/// [`crate::hi_z_pass::HiZPass`]'s doc comment for what's blocking the
+/// end-to-end occlusion-culling pipeline this feeds into.
 pub struct ComputeOccluderCirclesPass {
     device: Device,
     bind_group_layout: BindGroupLayout<ResourcesLayout>,
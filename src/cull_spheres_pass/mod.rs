@@ -0,0 +1,155 @@
+use bytemuck::Zeroable;
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Storage, StorageBinding, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::{abi, buffer};
+
+use crate::sphere::Sphere;
+
+const GROUP_SIZE: u32 = 256;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+/// Mirrors the arguments consumed by `draw_indexed_indirect`, so this buffer
+/// doubles as both the compacted instance counter written by
+/// [`CullSpheresPass`] and the indirect draw arguments consumed by
+/// `SpheresPass`.
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+#[repr(C)]
+pub struct DrawIndexedIndirectArgs {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub first_instance: u32,
+}
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+struct Uniforms {
+    world_to_clip: abi::Mat4x4,
+    geometry_scale: f32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    uniforms: Uniform<'a, Uniforms>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    spheres: Storage<'a, [Sphere]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    visible_indices: Storage<'a, [u32], ReadWrite>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    indirect_args: Storage<'a, DrawIndexedIndirectArgs, ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+pub struct CullSpheresPassInput<'a, U0, U1, U2> {
+    pub world_to_clip: abi::Mat4x4,
+    pub index_count: u32,
+    pub spheres: buffer::View<'a, [Sphere], U0>,
+    pub visible_indices: buffer::View<'a, [u32], U1>,
+    pub indirect_args: buffer::View<'a, DrawIndexedIndirectArgs, U2>,
+    /// The bounding radius of the geometry drawn per sphere instance (see
+    /// [`crate::sphere_data::GeometryData::bounding_radius`]), so instances
+    /// whose mesh bounding sphere isn't already unit radius aren't culled too
+    /// aggressively. `1.0` for geometry whose bounding sphere already
+    /// matches `Sphere::radius`.
+    pub geometry_scale: f32,
+}
+
+pub struct CullSpheresPass {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+}
+
+impl CullSpheresPass {
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        CullSpheresPass {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn encode(
+        &self,
+        encoder: CommandEncoder,
+        input: CullSpheresPassInput<impl StorageBinding, impl StorageBinding, impl StorageBinding>,
+    ) -> CommandEncoder {
+        let CullSpheresPassInput {
+            world_to_clip,
+            index_count,
+            spheres,
+            visible_indices,
+            indirect_args,
+            geometry_scale,
+        } = input;
+
+        let uniforms = self.device.create_buffer(
+            Uniforms {
+                world_to_clip,
+                geometry_scale,
+            },
+            buffer::Usages::uniform_binding(),
+        );
+
+        // Reset the atomic instance counter and seed the static draw
+        // parameters before the shader compacts the visible indices into
+        // `visible_indices` and increments `instance_count`.
+        self.device.queue().write_buffer(
+            indirect_args,
+            &DrawIndexedIndirectArgs {
+                index_count,
+                instance_count: 0,
+                first_index: 0,
+                base_vertex: 0,
+                first_instance: 0,
+            },
+        );
+
+        let workgroups = (spheres.len() as u32).div_ceil(GROUP_SIZE);
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                uniforms: uniforms.uniform(),
+                spheres: spheres.storage(),
+                visible_indices: visible_indices.storage(),
+                indirect_args: indirect_args.storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
@@ -1,4 +1,4 @@
-use empa::buffer::{Buffer, Uniform};
+use empa::buffer::Buffer;
 use empa::command::{
     DrawIndexed, DrawIndexedCommandEncoder, RenderBundleEncoderDescriptor, RenderStateEncoder,
     ResourceBindingCommandEncoder,
@@ -9,13 +9,13 @@ use empa::render_pipeline::{
     RenderPipelineDescriptorBuilder, VertexStageBuilder,
 };
 use empa::shader_module::{shader_source, ShaderSource};
-use empa::texture::format::{depth24plus, rgba8unorm};
-use empa::type_flag::{O, X};
-use empa::{abi, buffer, CompareFunction};
+use empa::texture::format::depth24plus;
+use empa::{buffer, CompareFunction};
 use glam::{Mat4, Vec3, Vec4};
 
+use crate::camera_binding::CameraBinding;
 use crate::grid::Grid;
-use crate::renderer::MainPassBundle;
+use crate::renderer::{MainColorFormat, MainPassBundle, MAIN_COLOR_FORMAT};
 
 const SHADER: ShaderSource = shader_source!("shader.wgsl");
 
@@ -33,31 +33,16 @@ impl From<Vec4> for Vertex {
     }
 }
 
-#[derive(empa::abi::Sized, Clone, Copy, Debug)]
-struct Uniforms {
-    world_to_clip: abi::Mat4x4,
-}
-
-#[derive(empa::resource_binding::Resources)]
-struct Resources<'a> {
-    #[resource(binding = 0, visibility = "VERTEX | FRAGMENT")]
-    uniform_buffer: Uniform<'a, Uniforms>,
-}
-
-type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
-
 pub struct GridsPass {
-    device: Device,
-    uniforms: Buffer<Uniforms, buffer::Usages<O, O, O, X, O, O, X, O, O, O>>,
     render_bundle: MainPassBundle,
 }
 
 impl GridsPass {
-    pub async fn init(device: Device, grids: &[Grid]) -> Self {
+    pub async fn init(device: Device, camera_binding: &CameraBinding, grids: &[Grid]) -> Self {
         let shader = device.create_shader_module(&SHADER);
 
-        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
-        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+        let pipeline_layout =
+            device.create_pipeline_layout((camera_binding.bind_group_layout(),));
 
         let pipeline = device
             .create_render_pipeline(
@@ -72,7 +57,7 @@ impl GridsPass {
                     .fragment(
                         FragmentStageBuilder::begin(&shader, "frag_main")
                             .color_outputs(ColorOutput {
-                                format: rgba8unorm,
+                                format: MAIN_COLOR_FORMAT,
                                 write_mask: ColorWrite::All,
                             })
                             .finish(),
@@ -85,20 +70,6 @@ impl GridsPass {
             )
             .await;
 
-        let uniforms = device.create_buffer(
-            Uniforms {
-                world_to_clip: abi::Mat4x4::default(),
-            },
-            buffer::Usages::uniform_binding().and_copy_dst(),
-        );
-
-        let bind_group = device.create_bind_group(
-            &bind_group_layout,
-            Resources {
-                uniform_buffer: uniforms.uniform(),
-            },
-        );
-
         let mut vertex_data: Vec<Vertex> = Vec::new();
         let mut index_data: Vec<u16> = Vec::new();
         let mut index_offset = 0;
@@ -179,7 +150,7 @@ impl GridsPass {
         let indices: Buffer<[u16], _> = device.create_buffer(index_data, buffer::Usages::index());
 
         let render_bundle_encoder = device.create_render_bundle_encoder(
-            &RenderBundleEncoderDescriptor::new::<rgba8unorm>()
+            &RenderBundleEncoderDescriptor::new::<MainColorFormat>()
                 .depth_stencil_format::<depth24plus>(),
         );
 
@@ -187,7 +158,7 @@ impl GridsPass {
             .set_pipeline(&pipeline)
             .set_vertex_buffers(&vertices)
             .set_index_buffer(&indices)
-            .set_bind_groups(&bind_group)
+            .set_bind_groups(camera_binding.bind_group())
             .draw_indexed(DrawIndexed {
                 index_count: indices.len() as u32,
                 instance_count: 1,
@@ -197,18 +168,13 @@ impl GridsPass {
             })
             .finish();
 
-        GridsPass {
-            device,
-            uniforms,
-            render_bundle,
-        }
+        GridsPass { render_bundle }
     }
 
-    pub fn render_bundle(&self, world_to_clip: abi::Mat4x4) -> &MainPassBundle {
-        self.device
-            .queue()
-            .write_buffer(self.uniforms.view(), &Uniforms { world_to_clip });
-
+    /// Returns the pre-recorded render bundle; make sure `camera_binding` has
+    /// been updated for this frame (via `CameraBinding::update`) before this
+    /// bundle is executed.
+    pub fn render_bundle(&self) -> &MainPassBundle {
         &self.render_bundle
     }
 }
@@ -0,0 +1,280 @@
+use bytemuck::Zeroable;
+use empa::access_mode::{ReadWrite, Write};
+use empa::buffer::{Buffer, Storage, StorageBinding, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::{
+    BindGroupLayout, StorageTexture2D, Texture2D as SampledTexture2D,
+};
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::texture::format::{depth24plus, r32float};
+use empa::texture::{MipmapLevels, SampledTextureDescriptor, StorageTextureDescriptor, Texture2D, Texture2DDescriptor};
+use empa::type_flag::{O, X};
+use empa::{abi, buffer, texture};
+use glam::Mat4;
+
+use crate::sphere::Sphere;
+
+const SEED_SHADER: ShaderSource = shader_source!("seed.wgsl");
+const DOWNSAMPLE_SHADER: ShaderSource = shader_source!("downsample.wgsl");
+const CULL_SHADER: ShaderSource = shader_source!("cull.wgsl");
+
+const DOWNSAMPLE_WORKGROUP_SIZE: u32 = 8;
+const CULL_GROUP_SIZE: u32 = 256;
+
+#[derive(empa::resource_binding::Resources)]
+struct SeedResources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    src: SampledTexture2D<'a>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    dst: StorageTexture2D<'a, r32float, Write>,
+}
+
+type SeedResourcesLayout = <SeedResources<'static> as empa::resource_binding::Resources>::Layout;
+
+#[derive(empa::resource_binding::Resources)]
+struct DownsampleResources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    src: SampledTexture2D<'a>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    dst: StorageTexture2D<'a, r32float, Write>,
+}
+
+type DownsampleResourcesLayout =
+    <DownsampleResources<'static> as empa::resource_binding::Resources>::Layout;
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+struct Uniforms {
+    world_to_camera: abi::Mat4x4,
+    camera_to_clip: abi::Mat4x4,
+    depth_width: u32,
+    depth_height: u32,
+    mip_levels: u32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct CullResources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    uniforms: Uniform<'a, Uniforms>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    spheres: Storage<'a, [Sphere]>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    visibility: Storage<'a, [u32], ReadWrite>,
+    #[resource(binding = 3, visibility = "COMPUTE")]
+    hi_z: SampledTexture2D<'a>,
+}
+
+type CullResourcesLayout = <CullResources<'static> as empa::resource_binding::Resources>::Layout;
+
+/// Builds a hierarchical-Z (max) depth pyramid from an occluder depth buffer
+/// and tests `Sphere` bounds against it, producing a per-instance visibility
+/// buffer: `1` if the sphere might be visible, `0` if it's guaranteed hidden
+/// behind closer geometry. The depth pyramid itself is rebuilt on every
+/// [`HiZPass::cull`] call, sized to the depth buffer passed in, since the
+/// occluder depth buffer's resolution isn't known until then.
+///
+/// Not yet wired into `Renderer`/`main`'s frame loop: doing so needs a
+/// depth-only prepass ahead of the main color pass (`Renderer`'s
+/// `depth_texture` is currently only populated *within* that pass, and lacks
+/// `texture_binding` usage besides), plus threading the resulting visibility
+/// buffer through [`crate::compact_occluder_circles_pass::CompactOccluderCirclesPass`]
+/// and [`crate::occluder_circles_pass::OccluderCirclesPass::render_bundle_indirect`].
+pub struct HiZPass {
+    device: Device,
+    mip_levels: u32,
+    seed_bind_group_layout: BindGroupLayout<SeedResourcesLayout>,
+    seed_pipeline: ComputePipeline<(SeedResourcesLayout,)>,
+    downsample_bind_group_layout: BindGroupLayout<DownsampleResourcesLayout>,
+    downsample_pipeline: ComputePipeline<(DownsampleResourcesLayout,)>,
+    cull_bind_group_layout: BindGroupLayout<CullResourcesLayout>,
+    cull_pipeline: ComputePipeline<(CullResourcesLayout,)>,
+}
+
+impl HiZPass {
+    pub async fn init(device: Device, mip_levels: u32) -> Self {
+        let seed_shader = device.create_shader_module(&SEED_SHADER);
+        let downsample_shader = device.create_shader_module(&DOWNSAMPLE_SHADER);
+        let cull_shader = device.create_shader_module(&CULL_SHADER);
+
+        let seed_bind_group_layout = device.create_bind_group_layout::<SeedResourcesLayout>();
+        let seed_pipeline_layout = device.create_pipeline_layout(&seed_bind_group_layout);
+
+        let seed_pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&seed_pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&seed_shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        let downsample_bind_group_layout =
+            device.create_bind_group_layout::<DownsampleResourcesLayout>();
+        let downsample_pipeline_layout =
+            device.create_pipeline_layout(&downsample_bind_group_layout);
+
+        let downsample_pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&downsample_pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&downsample_shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        let cull_bind_group_layout = device.create_bind_group_layout::<CullResourcesLayout>();
+        let cull_pipeline_layout = device.create_pipeline_layout(&cull_bind_group_layout);
+
+        let cull_pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&cull_pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&cull_shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        HiZPass {
+            device,
+            mip_levels,
+            seed_bind_group_layout,
+            seed_pipeline,
+            downsample_bind_group_layout,
+            downsample_pipeline,
+            cull_bind_group_layout,
+            cull_pipeline,
+        }
+    }
+
+    /// Builds a `mip_levels`-deep max-depth pyramid from `depth_texture`
+    /// (`width` x `height`, which must match its actual size) and tests every
+    /// sphere in `spheres` against it under `world_to_camera`/`camera_to_clip`,
+    /// returning a freshly allocated per-instance visibility buffer.
+    pub fn cull(
+        &self,
+        depth_texture: &Texture2D<depth24plus, texture::Usages<X, X, O, O, O>>,
+        width: u32,
+        height: u32,
+        world_to_camera: Mat4,
+        camera_to_clip: Mat4,
+        spheres: buffer::View<[Sphere], impl StorageBinding>,
+    ) -> Buffer<[u32], buffer::Usages<O, O, O, O, O, O, O, X, O, O>> {
+        use empa_glam::ToAbi;
+
+        let pyramid = self.device.create_texture_2d(&Texture2DDescriptor {
+            format: r32float,
+            usage: texture::Usages::texture_binding().and_storage_binding(),
+            view_formats: (),
+            width,
+            height,
+            layers: 1,
+            mipmap_levels: MipmapLevels::Partial(self.mip_levels),
+        });
+
+        let mut encoder = self.device.create_command_encoder();
+
+        let seed_bind_group = self.device.create_bind_group(
+            &self.seed_bind_group_layout,
+            SeedResources {
+                src: depth_texture.sampled_image(&SampledTextureDescriptor::default()),
+                dst: pyramid.storage_image(&StorageTextureDescriptor {
+                    base_mip_level: 0,
+                    ..Default::default()
+                }),
+            },
+        );
+
+        encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.seed_pipeline)
+            .set_bind_groups(&seed_bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: width.div_ceil(DOWNSAMPLE_WORKGROUP_SIZE),
+                count_y: height.div_ceil(DOWNSAMPLE_WORKGROUP_SIZE),
+                count_z: 1,
+            })
+            .end();
+
+        let mut level_width = width;
+        let mut level_height = height;
+
+        for level in 1..self.mip_levels {
+            let next_width = (level_width / 2).max(1);
+            let next_height = (level_height / 2).max(1);
+
+            let downsample_bind_group = self.device.create_bind_group(
+                &self.downsample_bind_group_layout,
+                DownsampleResources {
+                    src: pyramid.sampled_image(&SampledTextureDescriptor {
+                        base_mip_level: level - 1,
+                        mip_level_count: 1,
+                        ..Default::default()
+                    }),
+                    dst: pyramid.storage_image(&StorageTextureDescriptor {
+                        base_mip_level: level,
+                        ..Default::default()
+                    }),
+                },
+            );
+
+            encoder = encoder
+                .begin_compute_pass()
+                .set_pipeline(&self.downsample_pipeline)
+                .set_bind_groups(&downsample_bind_group)
+                .dispatch_workgroups(DispatchWorkgroups {
+                    count_x: next_width.div_ceil(DOWNSAMPLE_WORKGROUP_SIZE),
+                    count_y: next_height.div_ceil(DOWNSAMPLE_WORKGROUP_SIZE),
+                    count_z: 1,
+                })
+                .end();
+
+            level_width = next_width;
+            level_height = next_height;
+        }
+
+        let visibility: Buffer<[u32], _> = self
+            .device
+            .create_slice_buffer_zeroed(spheres.len(), buffer::Usages::storage_binding());
+
+        let uniforms = self.device.create_buffer(
+            Uniforms {
+                world_to_camera: world_to_camera.to_abi(),
+                camera_to_clip: camera_to_clip.to_abi(),
+                depth_width: width,
+                depth_height: height,
+                mip_levels: self.mip_levels,
+            },
+            buffer::Usages::uniform_binding(),
+        );
+
+        let cull_bind_group = self.device.create_bind_group(
+            &self.cull_bind_group_layout,
+            CullResources {
+                uniforms: uniforms.uniform(),
+                spheres: spheres.storage(),
+                visibility: visibility.storage(),
+                hi_z: pyramid.sampled_image(&SampledTextureDescriptor::default()),
+            },
+        );
+
+        let workgroups = (spheres.len() as u32).div_ceil(CULL_GROUP_SIZE);
+
+        encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.cull_pipeline)
+            .set_bind_groups(&cull_bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end();
+
+        self.device.queue().submit(encoder.finish());
+
+        visibility
+    }
+}
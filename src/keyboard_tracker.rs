@@ -0,0 +1,64 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use arwa::spawn_local;
+use arwa::ui::UiEventTarget;
+use arwa::window::window;
+use futures::future::{AbortHandle, Abortable};
+use futures::{future, StreamExt};
+
+/// Tracks which keys are currently held down, accumulating state from
+/// `on_key_down`/`on_key_up` events on the global [`window`], analogous to
+/// how [`MouseMovementTracker`](crate::mouse_movement_tracker::MouseMovementTracker)
+/// accumulates pointer movement.
+pub struct KeyboardTracker {
+    held_keys: Rc<RefCell<HashSet<String>>>,
+    key_down_abort_handle: AbortHandle,
+    key_up_abort_handle: AbortHandle,
+}
+
+impl KeyboardTracker {
+    pub fn new() -> Self {
+        let held_keys = Rc::new(RefCell::new(HashSet::new()));
+
+        let held_keys_clone = held_keys.clone();
+
+        let (key_down_abort_handle, key_down_abort_registration) = AbortHandle::new_pair();
+        let on_key_down = Abortable::new(window().on_key_down(), key_down_abort_registration);
+
+        spawn_local(on_key_down.for_each(move |event| {
+            held_keys_clone.borrow_mut().insert(event.code());
+
+            future::ready(())
+        }));
+
+        let held_keys_clone = held_keys.clone();
+
+        let (key_up_abort_handle, key_up_abort_registration) = AbortHandle::new_pair();
+        let on_key_up = Abortable::new(window().on_key_up(), key_up_abort_registration);
+
+        spawn_local(on_key_up.for_each(move |event| {
+            held_keys_clone.borrow_mut().remove(&event.code());
+
+            future::ready(())
+        }));
+
+        KeyboardTracker {
+            held_keys,
+            key_down_abort_handle,
+            key_up_abort_handle,
+        }
+    }
+
+    pub fn is_held(&self, code: &str) -> bool {
+        self.held_keys.borrow().contains(code)
+    }
+}
+
+impl Drop for KeyboardTracker {
+    fn drop(&mut self) {
+        self.key_down_abort_handle.abort();
+        self.key_up_abort_handle.abort();
+    }
+}
@@ -0,0 +1,10 @@
+use bytemuck::Zeroable;
+use empa::abi;
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug, Zeroable)]
+#[repr(C)]
+pub struct Light {
+    pub position: abi::Vec3<f32>,
+    pub color: abi::Vec3<f32>,
+    pub intensity: f32,
+}
@@ -0,0 +1,144 @@
+use empa::buffer;
+use empa::buffer::Storage;
+use empa::command::{
+    DrawIndexed, DrawIndexedCommandEncoder, RenderBundleEncoderDescriptor, RenderStateEncoder,
+    ResourceBindingCommandEncoder,
+};
+use empa::device::Device;
+use empa::render_pipeline::{
+    ColorOutput, ColorWrite, DepthStencilTest, FragmentStageBuilder, IndexAny, PrimitiveAssembly,
+    RenderPipeline, RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::texture::format::depth24plus;
+use empa::CompareFunction;
+
+use crate::camera_binding::{CameraBindGroupLayout, CameraBinding};
+use crate::light::Light;
+use crate::renderer::{MainColorFormat, MainPassBundle, MainPassLayout, MAIN_COLOR_FORMAT};
+use crate::shadow_pass::{ShadowPass, ShadowResourcesLayout};
+use crate::sphere::Sphere;
+use crate::sphere_data::{GeometryData, Vertex};
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "VERTEX")]
+    spheres: Storage<'a, [Sphere]>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    lights: Storage<'a, [Light]>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+pub struct LitSpheresPass {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: RenderPipeline<
+        MainPassLayout,
+        Vertex,
+        IndexAny,
+        (CameraBindGroupLayout, ResourcesLayout, ShadowResourcesLayout),
+    >,
+}
+
+impl LitSpheresPass {
+    pub async fn init(
+        device: Device,
+        camera_binding: &CameraBinding,
+        shadow_pass: &ShadowPass,
+    ) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout((
+            camera_binding.bind_group_layout(),
+            &bind_group_layout,
+            shadow_pass.bind_group_layout(),
+        ));
+
+        let pipeline = device
+            .create_render_pipeline(
+                &RenderPipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .primitive_assembly(PrimitiveAssembly::triangle_list())
+                    .vertex(
+                        VertexStageBuilder::begin(&shader, "vert_main")
+                            .vertex_layout::<Vertex>()
+                            .finish(),
+                    )
+                    .fragment(
+                        FragmentStageBuilder::begin(&shader, "frag_main")
+                            .color_outputs(ColorOutput {
+                                format: MAIN_COLOR_FORMAT,
+                                write_mask: ColorWrite::All,
+                            })
+                            .finish(),
+                    )
+                    .depth_stencil_test(
+                        DepthStencilTest::read_write::<depth24plus>()
+                            .depth_compare(CompareFunction::LessEqual),
+                    )
+                    .finish(),
+            )
+            .await;
+
+        LitSpheresPass {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn render_bundle<U0, U1>(
+        &self,
+        camera_binding: &CameraBinding,
+        shadow_pass: &ShadowPass,
+        geometry: &impl GeometryData,
+        spheres: buffer::View<[Sphere], U0>,
+        lights: buffer::View<[Light], U1>,
+    ) -> Option<MainPassBundle>
+    where
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+    {
+        if spheres.len() == 0 {
+            return None;
+        }
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                spheres: spheres.storage(),
+                lights: lights.storage(),
+            },
+        );
+
+        let render_bundle_encoder = self.device.create_render_bundle_encoder(
+            &RenderBundleEncoderDescriptor::new::<MainColorFormat>()
+                .depth_stencil_format::<depth24plus>(),
+        );
+
+        let bundle = render_bundle_encoder
+            .set_pipeline(&self.pipeline)
+            .set_vertex_buffers(geometry.vertices())
+            .set_index_buffer(geometry.indices())
+            .set_bind_groups((
+                camera_binding.bind_group(),
+                &bind_group,
+                shadow_pass.bind_group(),
+            ))
+            .draw_indexed(DrawIndexed {
+                index_count: geometry.indices().len() as u32,
+                instance_count: spheres.len() as u32,
+                first_index: 0,
+                base_vertex: 0,
+                first_instance: 0,
+            })
+            .finish();
+
+        Some(bundle)
+    }
+}
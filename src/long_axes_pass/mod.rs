@@ -11,10 +11,10 @@ use empa::render_pipeline::{
 };
 use empa::resource_binding::BindGroupLayout;
 use empa::shader_module::{shader_source, ShaderSource};
-use empa::texture::format::{depth24plus, rgba8unorm};
+use empa::texture::format::depth24plus;
 
 use crate::line::Line;
-use crate::renderer::{MainPassBundle, MainPassLayout};
+use crate::renderer::{MainColorFormat, MainPassBundle, MainPassLayout, MAIN_COLOR_FORMAT};
 
 const SHADER: ShaderSource = shader_source!("shader.wgsl");
 
@@ -53,7 +53,7 @@ impl LongAxesPass {
                     .fragment(
                         FragmentStageBuilder::begin(&shader, "frag_main")
                             .color_outputs(ColorOutput {
-                                format: rgba8unorm,
+                                format: MAIN_COLOR_FORMAT,
                                 write_mask: ColorWrite::All,
                             })
                             .finish(),
@@ -89,7 +89,7 @@ impl LongAxesPass {
         );
 
         let render_bundle_encoder = self.device.create_render_bundle_encoder(
-            &RenderBundleEncoderDescriptor::new::<rgba8unorm>()
+            &RenderBundleEncoderDescriptor::new::<MainColorFormat>()
                 .depth_stencil_format::<depth24plus>(),
         );
 
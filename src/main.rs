@@ -2,21 +2,37 @@
 
 pub mod bounding_rects_pass;
 pub mod camera;
+pub mod camera_binding;
 pub mod camera_controller;
+pub mod circle;
+pub mod circle_outline_pass;
+pub mod compact_occluder_circles_pass;
 pub mod compute_bounds_pass;
 pub mod compute_long_axis_pass;
+pub mod compute_occluder_circles_pass;
+pub mod cull_spheres_pass;
 pub mod grid;
 pub mod grids_pass;
+pub mod hi_z_pass;
+pub mod keyboard_tracker;
+pub mod light;
 pub mod line;
+pub mod lit_spheres_pass;
 pub mod long_axes_pass;
+pub mod mesh_data;
 pub mod mouse_movement_tracker;
+pub mod occluder_circles_pass;
 pub mod optics;
 pub mod renderer;
+pub mod shadow_pass;
 pub mod sky_gradient_pass;
 pub mod sphere;
 pub mod sphere_bounds;
 pub mod sphere_data;
+pub mod sphere_simulation_pass;
 pub mod spheres_pass;
+pub mod tonemap_pass;
+pub mod velocity;
 
 use std::error::Error;
 use std::f32::consts::PI;
@@ -29,7 +45,7 @@ use arwa::ui::UiEventTarget;
 use arwa::window::window;
 use empa::adapter::Feature;
 use empa::arwa::{NavigatorExt, PowerPreference, RequestAdapterOptions};
-use empa::{abi, buffer};
+use empa::buffer;
 use empa::buffer::{Buffer, BufferUsages};
 use empa::device::DeviceDescriptor;
 use empa_glam::ToAbi;
@@ -37,16 +53,25 @@ use futures::{FutureExt, StreamExt};
 use glam::{Quat, Vec3};
 
 use crate::camera::{Camera, CameraDescriptor};
+use crate::camera_binding::CameraBinding;
 use crate::camera_controller::CameraController;
 use crate::compute_bounds_pass::{ComputeSphereBounds, ComputeSphereBoundsInput};
 use crate::compute_long_axis_pass::{ComputeLongAxesPass, ComputeLongAxesPassInput};
+use crate::cull_spheres_pass::{CullSpheresPass, CullSpheresPassInput, DrawIndexedIndirectArgs};
 use crate::grid::{Grid, GridDescriptor};
+use crate::light::Light;
 use crate::line::Line;
 use crate::optics::{Lens, PerspectiveLens};
 use crate::renderer::{Renderer, RendererConfig};
+use crate::shadow_pass::ShadowPassDescriptor;
 use crate::sphere::Sphere;
 use crate::sphere_bounds::SphereBounds;
-use crate::sphere_data::SphereData;
+use crate::sphere_data::{GeometryData, SphereData};
+use crate::sphere_simulation_pass::{
+    EmitterDescriptor, SphereSimulationPass, SphereSimulationPassInput,
+};
+use crate::tonemap_pass::TonemapOperator;
+use crate::velocity::Velocity;
 
 fn main() {
     std::panic::set_hook(Box::new(console_error_panic_hook::hook));
@@ -89,9 +114,24 @@ async fn render() -> Result<(), Box<dyn Error>> {
     });
     let camera_controller = CameraController::init(&camera, &canvas);
 
+    camera_controller.add_viewpoint(
+        "front",
+        camera_controller::CameraTransform::new(Vec3::new(0.0, 0.0, 5.0), Quat::IDENTITY),
+    );
+    camera_controller.add_viewpoint(
+        "overhead",
+        camera_controller::CameraTransform::new(
+            Vec3::new(0.0, 5.0, 0.0),
+            Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), -0.5 * PI),
+        ),
+    );
+
+    let camera_binding = CameraBinding::init(device.clone());
+
     let mut renderer = Renderer::init(
         device.clone(),
         canvas,
+        &camera_binding,
         RendererConfig {
             grids: &[Grid::from(GridDescriptor {
                 scale: 1.0,
@@ -102,21 +142,68 @@ async fn render() -> Result<(), Box<dyn Error>> {
             })],
             gradient_bottom: Vec3::new(0.2, 0.2, 0.22),
             gradient_top: Vec3::new(0.9, 0.9, 0.95),
+            exposure: 1.0,
+            tonemap_operator: TonemapOperator::Aces,
+            shadow: ShadowPassDescriptor {
+                resolution: 512,
+                blur_radius: 2,
+                bias: 0.01,
+                light_bleed_bias: 0.2,
+            },
         },
     )
     .await;
-    let compute_sphere_bounds = ComputeSphereBounds::init(device.clone()).await;
-    let compute_long_axes = ComputeLongAxesPass::init(device.clone()).await;
+
+    // The first light in `lights` below doubles as the shadow-casting light.
+    let shadow_light_position = Vec3::new(3.0, 4.0, 3.0);
+    let compute_sphere_bounds = ComputeSphereBounds::init(device.clone(), &camera_binding).await;
+    let compute_long_axes = ComputeLongAxesPass::init(device.clone(), &camera_binding).await;
+    let cull_spheres = CullSpheresPass::init(device.clone()).await;
+    let sphere_simulation = SphereSimulationPass::init(device.clone()).await;
 
     let sphere_data = SphereData::new(&device, 20);
-    let spheres: Buffer<[Sphere], _> = device.create_buffer(
-        [Sphere {
-            origin: abi::Vec3(0.0, 0.0, 0.0),
-            radius: 1.0,
-        }],
+
+    let lights: Buffer<[Light], _> = device.create_buffer(
+        [
+            Light {
+                position: Vec3::new(3.0, 4.0, 3.0).to_abi(),
+                color: Vec3::new(1.0, 1.0, 1.0).to_abi(),
+                intensity: 40.0,
+            },
+            Light {
+                position: Vec3::new(-3.0, 2.0, -2.0).to_abi(),
+                color: Vec3::new(0.4, 0.5, 1.0).to_abi(),
+                intensity: 20.0,
+            },
+        ],
         buffer::Usages::storage_binding(),
     );
 
+    let (sphere_seed, velocity_seed) = sphere_simulation_pass::init_from_distribution(EmitterDescriptor {
+        count: 200,
+        sphere_radius: 0.1,
+        disk_radius: 0.0..2.0,
+        vertical_speed: 1.0..4.0,
+    });
+
+    let spheres: Buffer<[Sphere], _> =
+        device.create_buffer(sphere_seed, buffer::Usages::storage_binding());
+    let velocities: Buffer<[Velocity], _> =
+        device.create_buffer(velocity_seed, buffer::Usages::storage_binding());
+
+    let visible_sphere_indices: Buffer<[u32], _> = device
+        .create_slice_buffer_zeroed(spheres.len(), buffer::Usages::storage_binding());
+    let sphere_draw_args: Buffer<DrawIndexedIndirectArgs, _> = device.create_buffer(
+        DrawIndexedIndirectArgs {
+            index_count: sphere_data.indices.len() as u32,
+            instance_count: 0,
+            first_index: 0,
+            base_vertex: 0,
+            first_instance: 0,
+        },
+        buffer::Usages::storage_binding().and_indirect().and_copy_dst(),
+    );
+
     let sphere_bounds: Buffer<[SphereBounds], _> = device.create_slice_buffer_zeroed(spheres.len(), buffer::Usages::storage_binding().and_copy_src());
     let sphere_bounds = Rc::new(sphere_bounds);
 
@@ -175,28 +262,57 @@ async fn render() -> Result<(), Box<dyn Error>> {
         }
     });
 
+    let mut last_frame_time = window.performance().now();
+
     loop {
         window.request_animation_frame().await;
 
-        camera_controller.update_camera(&mut camera);
+        let frame_time = window.performance().now();
+        let dt = ((frame_time - last_frame_time) / 1000.0) as f32;
+        last_frame_time = frame_time;
+
+        camera_controller.update_camera(&mut camera, dt);
+        camera_binding.update(&camera);
 
         let mut encoder = device.create_command_encoder();
 
-        encoder = compute_sphere_bounds.encode(encoder, ComputeSphereBoundsInput {
-            world_to_camera: camera.world_to_camera().to_abi(),
-            camera_to_clip: camera.lens().camera_to_clip().to_abi(),
+        encoder = sphere_simulation.encode(encoder, SphereSimulationPassInput {
+            dt,
+            gravity: 9.81,
+            spheres: spheres.view(),
+            velocities: velocities.view(),
+        });
+        encoder = compute_sphere_bounds.encode(encoder, &camera_binding, ComputeSphereBoundsInput {
             spheres: spheres.view(),
             sphere_bounds: sphere_bounds.view(),
+            geometry_scale: sphere_data.bounding_radius(),
         });
-        encoder = compute_long_axes.encode(encoder, ComputeLongAxesPassInput {
-            world_to_camera: camera.world_to_camera().to_abi(),
-            camera_to_clip: camera.lens().camera_to_clip().to_abi(),
+        encoder = compute_long_axes.encode(encoder, &camera_binding, ComputeLongAxesPassInput {
             spheres: spheres.view(),
             long_axes: long_axes.view(),
         });
+        encoder = cull_spheres.encode(encoder, CullSpheresPassInput {
+            world_to_clip: camera.world_to_clip().to_abi(),
+            index_count: sphere_data.indices.len() as u32,
+            spheres: spheres.view(),
+            visible_indices: visible_sphere_indices.view(),
+            indirect_args: sphere_draw_args.view(),
+            geometry_scale: sphere_data.bounding_radius(),
+        });
 
         device.queue().submit(encoder.finish());
 
-        renderer.render(&sphere_data, spheres.view(), sphere_bounds.view(), long_axes.view(), &camera).await;
+        renderer.render(
+            &camera_binding,
+            &sphere_data,
+            spheres.view(),
+            visible_sphere_indices.view(),
+            sphere_draw_args.view(),
+            sphere_bounds.view(),
+            long_axes.view(),
+            lights.view(),
+            shadow_light_position,
+            &camera,
+        ).await;
     }
 }
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use empa::buffer;
+use empa::buffer::Buffer;
+use empa::device::Device;
+use empa::type_flag::{O, X};
+use glam::Vec3;
+
+use crate::sphere_data::{GeometryData, Vertex};
+
+/// An indexed triangle mesh loaded from an OBJ asset, interchangeable with
+/// [`SphereData`](crate::sphere_data::SphereData) wherever [`GeometryData`]
+/// is expected — this is what lets `SpheresPass` draw arbitrary models
+/// instead of only the generated icosphere.
+pub struct MeshData {
+    pub vertices: Buffer<[Vertex], buffer::Usages<O, O, O, O, X, O, O, O, O, O>>,
+    pub indices: Buffer<[u32], buffer::Usages<O, O, O, O, O, X, O, O, O, O>>,
+    bounding_radius: f32,
+}
+
+impl MeshData {
+    /// Parses the `v` and `f` records of a triangulated Wavefront OBJ asset
+    /// into an indexed vertex/index buffer pair, deduplicating vertices whose
+    /// face references (`v`, `v/vt` or `v/vt/vn`) are identical. Only
+    /// positions are stored; `vn` and `vt` records are not read.
+    pub fn from_obj(device: &Device, source: &str) -> Self {
+        let mut positions = Vec::new();
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut vertex_cache = HashMap::new();
+        let mut indices = Vec::new();
+        let mut bounding_radius_sq: f32 = 0.0;
+
+        for line in source.lines() {
+            let mut tokens = line.split_whitespace();
+
+            match tokens.next() {
+                Some("v") => {
+                    let position = parse_vec3(tokens);
+
+                    bounding_radius_sq = bounding_radius_sq.max(position.length_squared());
+                    positions.push(position);
+                }
+                Some("f") => {
+                    for token in tokens {
+                        let index = *vertex_cache.entry(token).or_insert_with(|| {
+                            // `token` is of the form `v`, `v/vt` or `v/vt/vn`; the whole
+                            // token is the dedup key, but only the position reference is
+                            // read, since `Vertex` holds a position only.
+                            let position_index: usize = token
+                                .split('/')
+                                .next()
+                                .and_then(|s| s.parse::<usize>().ok())
+                                .expect("malformed face record")
+                                - 1;
+
+                            let new_index = vertices.len() as u32;
+
+                            vertices.push(Vertex::from(positions[position_index]));
+
+                            new_index
+                        });
+
+                        indices.push(index);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let vertex_buffer = device.create_buffer(vertices, buffer::Usages::vertex());
+        let index_buffer = device.create_buffer(indices, buffer::Usages::index());
+
+        MeshData {
+            vertices: vertex_buffer,
+            indices: index_buffer,
+            bounding_radius: bounding_radius_sq.sqrt(),
+        }
+    }
+}
+
+impl GeometryData for MeshData {
+    fn vertices(&self) -> &Buffer<[Vertex], buffer::Usages<O, O, O, O, X, O, O, O, O, O>> {
+        &self.vertices
+    }
+
+    fn indices(&self) -> &Buffer<[u32], buffer::Usages<O, O, O, O, O, X, O, O, O, O>> {
+        &self.indices
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        self.bounding_radius
+    }
+}
+
+fn parse_vec3<'a>(mut tokens: impl Iterator<Item = &'a str>) -> Vec3 {
+    let mut next = || {
+        tokens
+            .next()
+            .expect("malformed vertex record")
+            .parse::<f32>()
+            .expect("malformed vertex record")
+    };
+
+    Vec3::new(next(), next(), next())
+}
@@ -3,7 +3,8 @@ use std::ops::Rem;
 
 use empa::buffer::{Buffer, BufferUsages, Storage};
 use empa::command::{
-    DrawIndexed, DrawIndexedCommandEncoder, RenderBundleEncoderDescriptor, RenderStateEncoder,
+    DrawIndexed, DrawIndexedCommandEncoder, DrawIndexedIndirect,
+    DrawIndexedIndirectCommandEncoder, RenderBundleEncoderDescriptor, RenderStateEncoder,
     ResourceBindingCommandEncoder,
 };
 use empa::device::Device;
@@ -18,6 +19,7 @@ use empa::texture::format::{depth24plus, rgba8unorm};
 use empa::{abi, buffer};
 
 use crate::circle::Circle;
+use crate::cull_spheres_pass::DrawIndexedIndirectArgs;
 use crate::renderer::{MainPassBundle, MainPassLayout};
 
 const SHADER: ShaderSource = shader_source!("shader.wgsl");
@@ -167,4 +169,42 @@ impl OccluderCirclesPass {
 
         Some(bundle)
     }
+
+    /// Like [`OccluderCirclesPass::render_bundle`], but draws a GPU-compacted
+    /// instance list (such as one produced by
+    /// [`crate::compact_occluder_circles_pass::CompactOccluderCirclesPass`])
+    /// via `draw_indexed_indirect`, so the actual instance count is decided
+    /// on the GPU and large, mostly-culled sphere sets skip the CPU
+    /// round-trip of `occluder_circles.len()`.
+    ///
+    /// Not yet called from `Renderer`/`main`'s frame loop — see
+    /// [`crate::hi_z_pass::HiZPass`]'s doc comment for what's blocking the
+    /// end-to-end occlusion-culling pipeline this is part of.
+    pub fn render_bundle_indirect(
+        &self,
+        compacted_instances: buffer::View<[Circle], impl buffer::StorageBinding>,
+        indirect_args: buffer::View<DrawIndexedIndirectArgs, impl buffer::IndirectBinding>,
+    ) -> MainPassBundle {
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                occluder_circles: compacted_instances.storage(),
+            },
+        );
+
+        let render_bundle_encoder = self.device.create_render_bundle_encoder(
+            &RenderBundleEncoderDescriptor::new::<rgba8unorm>()
+                .depth_stencil_format::<depth24plus>(),
+        );
+
+        render_bundle_encoder
+            .set_pipeline(&self.pipeline)
+            .set_vertex_buffers(&self.vertices)
+            .set_index_buffer(&self.indices)
+            .set_bind_groups(&bind_group)
+            .draw_indexed_indirect(DrawIndexedIndirect {
+                indirect_buffer: indirect_args,
+            })
+            .finish()
+    }
 }
@@ -1,11 +1,19 @@
 use std::ops::{Deref, DerefMut};
 
 use glam::f32::Mat4;
+use glam::Vec4;
 
 pub trait Lens {
     fn camera_to_clip(&self) -> Mat4;
 
     fn set_aspect_ratio(&mut self, aspect_ratio: f32);
+
+    /// Extracts the six camera-space frustum planes from `camera_to_clip`
+    /// (Gribb-Hartmann), normalized so that `plane.dot(p.extend(1.0))` gives
+    /// the signed distance from a camera-space point `p` to the plane.
+    fn frustum_planes(&self) -> [Vec4; 6] {
+        frustum_planes_from_clip(self.camera_to_clip())
+    }
 }
 
 impl<L> Lens for Box<L>
@@ -19,6 +27,38 @@ where
     fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
         self.deref_mut().set_aspect_ratio(aspect_ratio)
     }
+
+    fn frustum_planes(&self) -> [Vec4; 6] {
+        self.deref().frustum_planes()
+    }
+}
+
+/// Extracts the six frustum planes from a combined projection (or
+/// view-projection) matrix by adding/subtracting its rows per the
+/// Gribb-Hartmann method, in order `[left, right, bottom, top, near, far]`.
+/// Assumes glam's `[0, 1]` NDC z-range (as produced by `Mat4::perspective_rh`/
+/// `Mat4::orthographic_rh`), where the near plane is `row2` alone rather than
+/// `row3 + row2` (the `[-1, 1]`-NDC-z form).
+pub(crate) fn frustum_planes_from_clip(m: Mat4) -> [Vec4; 6] {
+    let row0 = Vec4::new(m.x_axis.x, m.y_axis.x, m.z_axis.x, m.w_axis.x);
+    let row1 = Vec4::new(m.x_axis.y, m.y_axis.y, m.z_axis.y, m.w_axis.y);
+    let row2 = Vec4::new(m.x_axis.z, m.y_axis.z, m.z_axis.z, m.w_axis.z);
+    let row3 = Vec4::new(m.x_axis.w, m.y_axis.w, m.z_axis.w, m.w_axis.w);
+
+    let mut planes = [
+        row3 + row0,
+        row3 - row0,
+        row3 + row1,
+        row3 - row1,
+        row2,
+        row3 - row2,
+    ];
+
+    for plane in &mut planes {
+        *plane /= plane.truncate().length();
+    }
+
+    planes
 }
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -45,3 +85,55 @@ impl Lens for PerspectiveLens {
         self.aspect_ratio = aspect_ratio;
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OrthographicLens {
+    pub left: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub top: f32,
+    pub frustum_near: f32,
+    pub frustum_far: f32,
+}
+
+impl OrthographicLens {
+    /// Convenience constructor for a symmetric orthographic frustum of the
+    /// given vertical `height`, centered on the view axis.
+    pub fn symmetric(height: f32, aspect_ratio: f32, frustum_near: f32, frustum_far: f32) -> Self {
+        let half_height = 0.5 * height;
+        let half_width = half_height * aspect_ratio;
+
+        OrthographicLens {
+            left: -half_width,
+            right: half_width,
+            bottom: -half_height,
+            top: half_height,
+            frustum_near,
+            frustum_far,
+        }
+    }
+}
+
+impl Lens for OrthographicLens {
+    fn camera_to_clip(&self) -> Mat4 {
+        let OrthographicLens {
+            left,
+            right,
+            bottom,
+            top,
+            frustum_near,
+            frustum_far,
+        } = *self;
+
+        Mat4::orthographic_rh(left, right, bottom, top, frustum_near, frustum_far)
+    }
+
+    fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        let height = self.top - self.bottom;
+        let half_width = 0.5 * height * aspect_ratio;
+        let center = 0.5 * (self.left + self.right);
+
+        self.left = center - half_width;
+        self.right = center + half_width;
+    }
+}
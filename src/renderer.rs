@@ -7,7 +7,7 @@ use empa::device::Device;
 use empa::render_target::{
     DepthAttachment, DepthValue, FloatAttachment, LoadOp, RenderLayout, RenderTarget, StoreOp,
 };
-use empa::texture::format::{depth24plus, rgba8unorm};
+use empa::texture::format::{depth24plus, rgba16float, rgba8unorm};
 use empa::texture::{AttachableImageDescriptor, MipmapLevels, Texture2D, Texture2DDescriptor};
 use empa::type_flag::{O, X};
 use empa::{buffer, texture};
@@ -16,41 +16,62 @@ use glam::Vec3;
 use crate::bounding_rects_pass::BoundingRectsPass;
 
 use crate::camera::Camera;
+use crate::camera_binding::CameraBinding;
+use crate::cull_spheres_pass::DrawIndexedIndirectArgs;
 use crate::grid::Grid;
 use crate::grids_pass::GridsPass;
+use crate::light::Light;
 use crate::line::Line;
+use crate::lit_spheres_pass::LitSpheresPass;
 use crate::long_axes_pass::LongAxesPass;
 use crate::optics::Lens;
+use crate::shadow_pass::{ShadowPass, ShadowPassDescriptor};
 use crate::sky_gradient_pass::SkyGradientPass;
 use crate::sphere::Sphere;
 use crate::sphere_bounds::SphereBounds;
-use crate::sphere_data::SphereData;
+use crate::sphere_data::GeometryData;
 use crate::spheres_pass::SpheresPass;
+use crate::tonemap_pass::{TonemapOperator, TonemapPass};
 
 pub struct RendererConfig<'a> {
     pub grids: &'a [Grid],
     pub gradient_bottom: Vec3,
     pub gradient_top: Vec3,
+    pub exposure: f32,
+    pub tonemap_operator: TonemapOperator,
+    pub shadow: ShadowPassDescriptor,
 }
 
-pub type MainPassLayout = RenderLayout<rgba8unorm, depth24plus>;
+/// The format the scene is rendered into before tone mapping. Using a
+/// floating point format lets the grid, sky and sphere passes accumulate
+/// values outside of the `0..1` range, which `TonemapPass` then resolves
+/// down to the swapchain's `rgba8unorm` image.
+pub type MainColorFormat = rgba16float;
+pub const MAIN_COLOR_FORMAT: MainColorFormat = rgba16float;
+
+pub type MainPassLayout = RenderLayout<MainColorFormat, depth24plus>;
 pub type MainPassBundle = RenderBundle<MainPassLayout>;
 
 pub struct Renderer {
     device: Device,
     context: ConfiguredCanvasContext<rgba8unorm, texture::Usages<X, O, O, O, O>>,
+    hdr_texture: Texture2D<MainColorFormat, texture::Usages<X, X, O, O, O>>,
     depth_texture: Texture2D<depth24plus, texture::Usages<X, O, O, O, O>>,
     grids_pass: GridsPass,
     sky_gradient_pass: SkyGradientPass,
     spheres_pass: SpheresPass,
+    shadow_pass: ShadowPass,
+    lit_spheres_pass: LitSpheresPass,
     bounding_rects_pass: BoundingRectsPass,
     long_axes_pass: LongAxesPass,
+    tonemap_pass: TonemapPass,
 }
 
 impl Renderer {
     pub async fn init(
         device: Device,
         canvas: HtmlCanvasElement,
+        camera_binding: &CameraBinding,
         config: RendererConfig<'_>,
     ) -> Self {
         let context = canvas.empa_context().configure(&CanvasConfiguration {
@@ -61,6 +82,16 @@ impl Renderer {
             alpha_mode: AlphaMode::Opaque,
         });
 
+        let hdr_texture = device.create_texture_2d(&Texture2DDescriptor {
+            format: rgba16float,
+            usage: texture::Usages::render_attachment().and_texture_binding(),
+            view_formats: (),
+            width: canvas.width(),
+            height: canvas.height(),
+            layers: 1,
+            mipmap_levels: MipmapLevels::Partial(1),
+        });
+
         let depth_texture = device.create_texture_2d(&Texture2DDescriptor {
             format: depth24plus,
             usage: texture::Usages::render_attachment(),
@@ -75,39 +106,75 @@ impl Renderer {
             grids,
             gradient_bottom,
             gradient_top,
+            exposure,
+            tonemap_operator,
+            shadow,
         } = config;
 
-        let init_grids_pass = GridsPass::init(device.clone(), grids);
+        // `LitSpheresPass` binds the shadow map as its third bind group, so
+        // `ShadowPass` needs to exist before it can be initialized.
+        let shadow_pass = ShadowPass::init(device.clone(), shadow).await;
+
+        let init_grids_pass = GridsPass::init(device.clone(), camera_binding, grids);
         let init_sky_gradient_pass = SkyGradientPass::init(
             device.clone(),
             gradient_bottom.to_abi(),
             gradient_top.to_abi(),
         );
         let init_spheres_pass = SpheresPass::init(device.clone());
+        let init_lit_spheres_pass =
+            LitSpheresPass::init(device.clone(), camera_binding, &shadow_pass);
         let init_bounding_rects_pass = BoundingRectsPass::init(device.clone());
         let init_long_axes_pass = LongAxesPass::init(device.clone());
+        let init_tonemap_pass =
+            TonemapPass::init(device.clone(), &hdr_texture, exposure, tonemap_operator);
 
-        let (grids_pass, sky_gradient_pass, spheres_pass, bounding_rects_pass, long_axes_pass) =
-            join!(init_grids_pass, init_sky_gradient_pass, init_spheres_pass, init_bounding_rects_pass, init_long_axes_pass).await;
+        let (
+            grids_pass,
+            sky_gradient_pass,
+            spheres_pass,
+            lit_spheres_pass,
+            bounding_rects_pass,
+            long_axes_pass,
+            tonemap_pass,
+        ) = join!(
+            init_grids_pass,
+            init_sky_gradient_pass,
+            init_spheres_pass,
+            init_lit_spheres_pass,
+            init_bounding_rects_pass,
+            init_long_axes_pass,
+            init_tonemap_pass
+        )
+        .await;
 
         Renderer {
             device,
             context,
+            hdr_texture,
             depth_texture,
             grids_pass,
             sky_gradient_pass,
             spheres_pass,
+            shadow_pass,
+            lit_spheres_pass,
             bounding_rects_pass,
             long_axes_pass,
+            tonemap_pass,
         }
     }
 
     pub async fn render(
         &mut self,
-        sphere_data: &SphereData,
+        camera_binding: &CameraBinding,
+        geometry: &impl GeometryData,
         spheres: buffer::View<'_, [Sphere], impl buffer::StorageBinding>,
+        visible_sphere_indices: buffer::View<'_, [u32], impl buffer::StorageBinding>,
+        sphere_draw_args: buffer::View<'_, DrawIndexedIndirectArgs, impl buffer::IndirectBinding>,
         sphere_bounds: buffer::View<'_, [SphereBounds], impl buffer::StorageBinding>,
         long_axes: buffer::View<'_, [Line], impl buffer::StorageBinding>,
+        lights: buffer::View<'_, [Light], impl buffer::StorageBinding>,
+        shadow_light_position: Vec3,
         camera: &Camera<impl Lens>,
     ) {
         let world_to_clip = camera.world_to_clip().to_abi();
@@ -115,22 +182,35 @@ impl Renderer {
         let camera_to_clip = camera.lens().camera_to_clip().to_abi();
         let clip_to_camera = camera.lens().camera_to_clip().inverse().to_abi();
 
-        let grids_bundle = self.grids_pass.render_bundle(world_to_clip);
+        let grids_bundle = self.grids_pass.render_bundle();
         let sky_bundle = self.sky_gradient_pass.render_bundle(clip_to_camera);
-        let spheres_bundle = self
-            .spheres_pass
-            .render_bundle(world_to_clip, sphere_data, spheres);
+        let spheres_bundle = self.spheres_pass.render_bundle(
+            world_to_clip,
+            geometry,
+            spheres,
+            visible_sphere_indices,
+            sphere_draw_args,
+        );
+        let lit_spheres_bundle = self.lit_spheres_pass.render_bundle(
+            camera_binding,
+            &self.shadow_pass,
+            geometry,
+            spheres,
+            lights,
+        );
         let bounding_rects_bundle = self.bounding_rects_pass.render_bundle(sphere_bounds);
         let long_axes_bundle = self.long_axes_pass.render_bundle(long_axes);
 
         let encoder = self.device.create_command_encoder();
+        let encoder = self
+            .shadow_pass
+            .encode(encoder, geometry, spheres, shadow_light_position);
 
         let mut render_pass_encoder =
             encoder.begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
                 color: FloatAttachment {
                     image: self
-                        .context
-                        .get_current_texture()
+                        .hdr_texture
                         .attachable_image(&AttachableImageDescriptor::default()),
                     load_op: LoadOp::Clear([0.0; 4]),
                     store_op: StoreOp::Store,
@@ -151,6 +231,10 @@ impl Renderer {
             render_pass_encoder = render_pass_encoder.execute_bundle(&spheres_bundle);
         }
 
+        if let Some(lit_spheres_bundle) = lit_spheres_bundle {
+            render_pass_encoder = render_pass_encoder.execute_bundle(&lit_spheres_bundle);
+        }
+
         if let Some(bounding_rects_bundle) = bounding_rects_bundle {
             render_pass_encoder = render_pass_encoder.execute_bundle(&bounding_rects_bundle);
         }
@@ -159,7 +243,26 @@ impl Renderer {
             render_pass_encoder = render_pass_encoder.execute_bundle(&long_axes_bundle);
         }
 
-        let command_buffer = render_pass_encoder.end().finish();
+        let encoder = render_pass_encoder.end();
+
+        let tonemap_bundle = self.tonemap_pass.render_bundle();
+
+        let mut tonemap_pass_encoder =
+            encoder.begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+                color: FloatAttachment {
+                    image: self
+                        .context
+                        .get_current_texture()
+                        .attachable_image(&AttachableImageDescriptor::default()),
+                    load_op: LoadOp::Clear([0.0; 4]),
+                    store_op: StoreOp::Store,
+                },
+                depth_stencil: (),
+            }));
+
+        tonemap_pass_encoder = tonemap_pass_encoder.execute_bundle(&tonemap_bundle);
+
+        let command_buffer = tonemap_pass_encoder.end().finish();
 
         self.device.queue().submit(command_buffer);
     }
@@ -0,0 +1,444 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bytemuck::Zeroable;
+use empa::access_mode::{Read, Write};
+use empa::buffer::{Buffer, Storage, Uniform};
+use empa::command::{
+    CommandEncoder, DispatchWorkgroups, DrawIndexed, DrawIndexedCommandEncoder,
+    RenderPassDescriptor, RenderStateEncoder, ResourceBindingCommandEncoder,
+};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::render_pipeline::{
+    ColorOutput, ColorWrite, DepthStencilTest, FragmentStageBuilder, IndexAny, PrimitiveAssembly,
+    RenderPipeline, RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use empa::render_target::{
+    DepthAttachment, DepthValue, FloatAttachment, LoadOp, RenderLayout, RenderTarget, StoreOp,
+};
+use empa::resource_binding::{
+    BindGroup, BindGroupLayout, Sampler, StorageTexture2DArray, Texture2DArray as SampledTexture2DArray,
+};
+use empa::sampler::{FilterMode, SamplerDescriptor};
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::texture::format::{depth24plus, rg32float};
+use empa::texture::{
+    AttachableImageDescriptor, MipmapLevels, SampledTextureDescriptor, StorageTextureDescriptor,
+    Texture2D, Texture2DDescriptor,
+};
+use empa::type_flag::{O, X};
+use empa::{abi, buffer, texture};
+use empa_glam::ToAbi;
+use glam::{Mat4, Vec3};
+
+use crate::sphere::Sphere;
+use crate::sphere_data::{GeometryData, Vertex};
+
+const MOMENTS_SHADER: ShaderSource = shader_source!("moments.wgsl");
+const BLUR_SHADER: ShaderSource = shader_source!("blur.wgsl");
+
+const BLUR_WORKGROUP_SIZE: u32 = 8;
+
+/// Configuration for [`ShadowPass`]. `resolution` is the per-face size of
+/// the moments cubemap; `blur_radius` controls the separable Gaussian blur
+/// applied to each face; `bias` is added to the stored distance to curb
+/// shadow acne; `light_bleed_bias` remaps the Chebyshev upper bound computed
+/// at shade time to reduce light bleeding.
+pub struct ShadowPassDescriptor {
+    pub resolution: u32,
+    pub blur_radius: u32,
+    pub bias: f32,
+    pub light_bleed_bias: f32,
+}
+
+type MomentsLayout = RenderLayout<rg32float, depth24plus>;
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+struct MomentsUniforms {
+    world_to_clip: abi::Mat4x4,
+    light_position: abi::Vec3<f32>,
+    bias: f32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct MomentsResources<'a> {
+    #[resource(binding = 0, visibility = "VERTEX | FRAGMENT")]
+    uniforms: Uniform<'a, MomentsUniforms>,
+    #[resource(binding = 1, visibility = "VERTEX")]
+    spheres: Storage<'a, [Sphere]>,
+}
+
+type MomentsResourcesLayout = <MomentsResources<'static> as empa::resource_binding::Resources>::Layout;
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+struct BlurUniforms {
+    radius: u32,
+    horizontal: u32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct BlurResources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    uniforms: Uniform<'a, BlurUniforms>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    moments_in: StorageTexture2DArray<'a, rg32float, Read>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    moments_out: StorageTexture2DArray<'a, rg32float, Write>,
+}
+
+type BlurResourcesLayout = <BlurResources<'static> as empa::resource_binding::Resources>::Layout;
+
+/// The uniform values a shading pass needs to sample the filtered variance
+/// shadow map: the shadow-casting light's world position (to recompute the
+/// fragment-to-light distance) and the light-bleed remap bias.
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+pub struct ShadowUniforms {
+    pub light_position: abi::Vec3<f32>,
+    pub light_bleed_bias: f32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct ShadowResources<'a> {
+    #[resource(binding = 0, visibility = "FRAGMENT")]
+    uniforms: Uniform<'a, ShadowUniforms>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    shadow_sampler: Sampler<'a>,
+    #[resource(binding = 2, visibility = "FRAGMENT")]
+    shadow_map: SampledTexture2DArray<'a>,
+}
+
+/// The bind group layout [`LitSpheresPass`](crate::lit_spheres_pass::LitSpheresPass)
+/// adds as a third pipeline bind group to sample the shadow map produced by
+/// [`ShadowPass`].
+pub type ShadowResourcesLayout = <ShadowResources<'static> as empa::resource_binding::Resources>::Layout;
+
+/// Six view directions and up-vectors, in the order the cubemap array layers
+/// are written: `+X, -X, +Y, -Y, +Z, -Z`.
+const FACE_AXES: [(Vec3, Vec3); 6] = [
+    (Vec3::X, Vec3::NEG_Y),
+    (Vec3::NEG_X, Vec3::NEG_Y),
+    (Vec3::Y, Vec3::Z),
+    (Vec3::NEG_Y, Vec3::NEG_Z),
+    (Vec3::Z, Vec3::NEG_Y),
+    (Vec3::NEG_Z, Vec3::NEG_Y),
+];
+
+/// Renders an omnidirectional variance shadow map for a single point light:
+/// six faces of linear-distance moments `(d, d^2)`, blurred with a separable
+/// Gaussian, stored as a 6-layer `rg32float` texture. Shading passes bind the
+/// result (see [`ShadowPass::bind_group_layout`] and [`ShadowPass::bind_group`])
+/// and apply Chebyshev's inequality to estimate how lit a fragment is.
+pub struct ShadowPass {
+    device: Device,
+    resolution: u32,
+    bias: f32,
+    light_bleed_bias: f32,
+    depth_texture: Texture2D<depth24plus, texture::Usages<X, O, O, O, O>>,
+    moments_texture: Texture2D<rg32float, texture::Usages<X, X, X, O, O>>,
+    scratch_texture: Texture2D<rg32float, texture::Usages<O, X, X, O, O>>,
+    moments_uniforms: [Buffer<MomentsUniforms, buffer::Usages<O, O, O, X, O, O, X, O, O, O>>; 6],
+    moments_bind_group_layout: BindGroupLayout<MomentsResourcesLayout>,
+    moments_pipeline: RenderPipeline<MomentsLayout, Vertex, IndexAny, (MomentsResourcesLayout,)>,
+    blur_bind_group_layout: BindGroupLayout<BlurResourcesLayout>,
+    blur_pipeline: ComputePipeline<(BlurResourcesLayout,)>,
+    blur_uniforms_horizontal: Buffer<BlurUniforms, buffer::Usages<O, O, O, X, O, O, X, O, O, O>>,
+    blur_uniforms_vertical: Buffer<BlurUniforms, buffer::Usages<O, O, O, X, O, O, X, O, O, O>>,
+    shadow_bind_group_layout: BindGroupLayout<ShadowResourcesLayout>,
+    shadow_uniforms: Buffer<ShadowUniforms, buffer::Usages<O, O, O, X, O, O, X, O, O, O>>,
+    bind_group: BindGroup<ShadowResourcesLayout>,
+}
+
+impl ShadowPass {
+    pub async fn init(device: Device, descriptor: ShadowPassDescriptor) -> Self {
+        let ShadowPassDescriptor {
+            resolution,
+            blur_radius,
+            bias,
+            light_bleed_bias,
+        } = descriptor;
+
+        let moments_shader = device.create_shader_module(&MOMENTS_SHADER);
+        let blur_shader = device.create_shader_module(&BLUR_SHADER);
+
+        let moments_bind_group_layout =
+            device.create_bind_group_layout::<MomentsResourcesLayout>();
+        let moments_pipeline_layout = device.create_pipeline_layout(&moments_bind_group_layout);
+
+        let moments_pipeline = device
+            .create_render_pipeline(
+                &RenderPipelineDescriptorBuilder::begin()
+                    .layout(&moments_pipeline_layout)
+                    .primitive_assembly(PrimitiveAssembly::triangle_list())
+                    .vertex(
+                        VertexStageBuilder::begin(&moments_shader, "vert_main")
+                            .vertex_layout::<Vertex>()
+                            .finish(),
+                    )
+                    .fragment(
+                        FragmentStageBuilder::begin(&moments_shader, "frag_main")
+                            .color_outputs(ColorOutput {
+                                format: rg32float,
+                                write_mask: ColorWrite::All,
+                            })
+                            .finish(),
+                    )
+                    .depth_stencil_test(DepthStencilTest::read_write::<depth24plus>())
+                    .finish(),
+            )
+            .await;
+
+        let blur_bind_group_layout = device.create_bind_group_layout::<BlurResourcesLayout>();
+        let blur_pipeline_layout = device.create_pipeline_layout(&blur_bind_group_layout);
+
+        let blur_pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&blur_pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&blur_shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        let shadow_bind_group_layout = device.create_bind_group_layout::<ShadowResourcesLayout>();
+
+        let depth_texture = device.create_texture_2d(&Texture2DDescriptor {
+            format: depth24plus,
+            usage: texture::Usages::render_attachment(),
+            view_formats: (),
+            width: resolution,
+            height: resolution,
+            layers: 1,
+            mipmap_levels: MipmapLevels::Partial(1),
+        });
+
+        let moments_texture = device.create_texture_2d(&Texture2DDescriptor {
+            format: rg32float,
+            usage: texture::Usages::render_attachment()
+                .and_texture_binding()
+                .and_storage_binding(),
+            view_formats: (),
+            width: resolution,
+            height: resolution,
+            layers: 6,
+            mipmap_levels: MipmapLevels::Partial(1),
+        });
+
+        let scratch_texture = device.create_texture_2d(&Texture2DDescriptor {
+            format: rg32float,
+            usage: texture::Usages::texture_binding().and_storage_binding(),
+            view_formats: (),
+            width: resolution,
+            height: resolution,
+            layers: 6,
+            mipmap_levels: MipmapLevels::Partial(1),
+        });
+
+        let moments_uniforms = std::array::from_fn(|_| {
+            device.create_buffer(
+                MomentsUniforms {
+                    world_to_clip: Zeroable::zeroed(),
+                    light_position: Zeroable::zeroed(),
+                    bias,
+                },
+                buffer::Usages::uniform_binding().and_copy_dst(),
+            )
+        });
+
+        let blur_uniforms_horizontal = device.create_buffer(
+            BlurUniforms {
+                radius: blur_radius,
+                horizontal: 1,
+            },
+            buffer::Usages::uniform_binding().and_copy_dst(),
+        );
+        let blur_uniforms_vertical = device.create_buffer(
+            BlurUniforms {
+                radius: blur_radius,
+                horizontal: 0,
+            },
+            buffer::Usages::uniform_binding().and_copy_dst(),
+        );
+
+        let shadow_uniforms = device.create_buffer(
+            ShadowUniforms {
+                light_position: Zeroable::zeroed(),
+                light_bleed_bias,
+            },
+            buffer::Usages::uniform_binding().and_copy_dst(),
+        );
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(
+            &shadow_bind_group_layout,
+            ShadowResources {
+                uniforms: shadow_uniforms.uniform(),
+                shadow_sampler: sampler.sampled(),
+                shadow_map: moments_texture.sampled_image(&SampledTextureDescriptor::default()),
+            },
+        );
+
+        ShadowPass {
+            device,
+            resolution,
+            bias,
+            light_bleed_bias,
+            depth_texture,
+            moments_texture,
+            scratch_texture,
+            moments_uniforms,
+            moments_bind_group_layout,
+            moments_pipeline,
+            blur_bind_group_layout,
+            blur_pipeline,
+            blur_uniforms_horizontal,
+            blur_uniforms_vertical,
+            shadow_bind_group_layout,
+            shadow_uniforms,
+            bind_group,
+        }
+    }
+
+    /// Re-renders and re-blurs the moments cubemap for a point light at
+    /// `light_position`, given the current sphere positions.
+    pub fn encode(
+        &self,
+        mut encoder: CommandEncoder,
+        geometry: &impl GeometryData,
+        spheres: buffer::View<[Sphere], impl buffer::StorageBinding>,
+        light_position: Vec3,
+    ) -> CommandEncoder {
+        self.device.queue().write_buffer(
+            self.shadow_uniforms.view(),
+            &ShadowUniforms {
+                light_position: light_position.to_abi(),
+                light_bleed_bias: self.light_bleed_bias,
+            },
+        );
+
+        let near = 0.05;
+        let far = 2.0 * light_position.length().max(1.0) + 50.0;
+        let projection = Mat4::perspective_rh(FRAC_PI_2, 1.0, near, far);
+
+        for (face, (direction, up)) in FACE_AXES.iter().enumerate() {
+            let view = Mat4::look_at_rh(light_position, light_position + *direction, *up);
+            let world_to_clip = projection * view;
+
+            self.device.queue().write_buffer(
+                self.moments_uniforms[face].view(),
+                &MomentsUniforms {
+                    world_to_clip: world_to_clip.to_abi(),
+                    light_position: light_position.to_abi(),
+                    bias: self.bias,
+                },
+            );
+
+            let bind_group = self.device.create_bind_group(
+                &self.moments_bind_group_layout,
+                MomentsResources {
+                    uniforms: self.moments_uniforms[face].uniform(),
+                    spheres: spheres.storage(),
+                },
+            );
+
+            let render_pass_encoder =
+                encoder.begin_render_pass(RenderPassDescriptor::new(&RenderTarget {
+                    color: FloatAttachment {
+                        image: self
+                            .moments_texture
+                            .attachable_image(&AttachableImageDescriptor::layer(face as u32)),
+                        load_op: LoadOp::Clear([0.0; 2]),
+                        store_op: StoreOp::Store,
+                    },
+                    depth_stencil: DepthAttachment {
+                        image: self
+                            .depth_texture
+                            .attachable_image(&AttachableImageDescriptor::default()),
+                        load_op: LoadOp::Clear(DepthValue::ONE),
+                        store_op: StoreOp::Discard,
+                    },
+                }));
+
+            let render_pass_encoder = render_pass_encoder
+                .set_pipeline(&self.moments_pipeline)
+                .set_vertex_buffers(geometry.vertices())
+                .set_index_buffer(geometry.indices())
+                .set_bind_groups(&bind_group)
+                .draw_indexed(DrawIndexed {
+                    index_count: geometry.indices().len() as u32,
+                    instance_count: spheres.len() as u32,
+                    first_index: 0,
+                    base_vertex: 0,
+                    first_instance: 0,
+                });
+
+            encoder = render_pass_encoder.end();
+        }
+
+        let workgroups = self.resolution.div_ceil(BLUR_WORKGROUP_SIZE);
+
+        let horizontal_bind_group = self.device.create_bind_group(
+            &self.blur_bind_group_layout,
+            BlurResources {
+                uniforms: self.blur_uniforms_horizontal.uniform(),
+                moments_in: self
+                    .moments_texture
+                    .storage_image(&StorageTextureDescriptor::default()),
+                moments_out: self
+                    .scratch_texture
+                    .storage_image(&StorageTextureDescriptor::default()),
+            },
+        );
+
+        encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.blur_pipeline)
+            .set_bind_groups(&horizontal_bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: workgroups,
+                count_z: 6,
+            })
+            .end();
+
+        let vertical_bind_group = self.device.create_bind_group(
+            &self.blur_bind_group_layout,
+            BlurResources {
+                uniforms: self.blur_uniforms_vertical.uniform(),
+                moments_in: self
+                    .scratch_texture
+                    .storage_image(&StorageTextureDescriptor::default()),
+                moments_out: self
+                    .moments_texture
+                    .storage_image(&StorageTextureDescriptor::default()),
+            },
+        );
+
+        encoder = encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.blur_pipeline)
+            .set_bind_groups(&vertical_bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: workgroups,
+                count_z: 6,
+            })
+            .end();
+
+        encoder
+    }
+
+    pub fn bind_group_layout(&self) -> &BindGroupLayout<ShadowResourcesLayout> {
+        &self.shadow_bind_group_layout
+    }
+
+    pub fn bind_group(&self) -> &BindGroup<ShadowResourcesLayout> {
+        &self.bind_group
+    }
+}
@@ -9,11 +9,11 @@ use empa::render_pipeline::{
     RenderPipelineDescriptorBuilder, VertexStageBuilder,
 };
 use empa::shader_module::{shader_source, ShaderSource};
-use empa::texture::format::{depth24plus, rgba8unorm};
+use empa::texture::format::depth24plus;
 use empa::type_flag::{O, X};
 use empa::{abi, buffer, CompareFunction};
 
-use crate::renderer::MainPassBundle;
+use crate::renderer::{MainColorFormat, MainPassBundle, MAIN_COLOR_FORMAT};
 
 const SHADER: ShaderSource = shader_source!("shader.wgsl");
 
@@ -70,7 +70,7 @@ impl SkyGradientPass {
                     .fragment(
                         FragmentStageBuilder::begin(&shader, "frag_main")
                             .color_outputs(ColorOutput {
-                                format: rgba8unorm,
+                                format: MAIN_COLOR_FORMAT,
                                 write_mask: ColorWrite::All,
                             })
                             .finish(),
@@ -118,7 +118,7 @@ impl SkyGradientPass {
         );
 
         let render_bundle_encoder = device.create_render_bundle_encoder(
-            &RenderBundleEncoderDescriptor::new::<rgba8unorm>()
+            &RenderBundleEncoderDescriptor::new::<MainColorFormat>()
                 .depth_stencil_format::<depth24plus>(),
         );
         let render_bundle = render_bundle_encoder
@@ -7,3 +7,73 @@ pub struct Sphere {
     pub origin: abi::Vec3<f32>,
     pub radius: f32,
 }
+
+impl Sphere {
+    pub fn contains_point(&self, point: abi::Vec3<f32>) -> bool {
+        let dx = point.x - self.origin.x;
+        let dy = point.y - self.origin.y;
+        let dz = point.z - self.origin.z;
+
+        dx * dx + dy * dy + dz * dz <= self.radius * self.radius
+    }
+
+    pub fn intersects_sphere(&self, other: &Sphere) -> bool {
+        let dx = other.origin.x - self.origin.x;
+        let dy = other.origin.y - self.origin.y;
+        let dz = other.origin.z - self.origin.z;
+        let radii = self.radius + other.radius;
+
+        dx * dx + dy * dy + dz * dz <= radii * radii
+    }
+
+    /// Tests this sphere against an axis-aligned box (`min`/`max` corners) by
+    /// clamping the sphere's origin into the box and checking the distance
+    /// to that closest point, so it also catches the case where the sphere
+    /// overlaps an edge or corner without its origin falling inside the box.
+    pub fn in_rectangle(&self, min: abi::Vec3<f32>, max: abi::Vec3<f32>) -> bool {
+        let closest_x = self.origin.x.clamp(min.x, max.x);
+        let closest_y = self.origin.y.clamp(min.y, max.y);
+        let closest_z = self.origin.z.clamp(min.z, max.z);
+
+        let dx = self.origin.x - closest_x;
+        let dy = self.origin.y - closest_y;
+        let dz = self.origin.z - closest_z;
+
+        dx * dx + dy * dy + dz * dz <= self.radius * self.radius
+    }
+
+    /// Returns the distance along `ray_direction` (assumed normalized) from
+    /// `ray_origin` to the nearest point where the ray enters the sphere (or,
+    /// if `ray_origin` lies inside the sphere, where the ray exits it), or
+    /// `None` if the ray misses it entirely. Useful for mouse picking:
+    /// project a screen-space cursor into a world-space ray and test it
+    /// against candidate spheres to find the closest hit.
+    pub fn ray_intersection(
+        &self,
+        ray_origin: abi::Vec3<f32>,
+        ray_direction: abi::Vec3<f32>,
+    ) -> Option<f32> {
+        let ox = ray_origin.x - self.origin.x;
+        let oy = ray_origin.y - self.origin.y;
+        let oz = ray_origin.z - self.origin.z;
+
+        let b = ox * ray_direction.x + oy * ray_direction.y + oz * ray_direction.z;
+        let c = ox * ox + oy * oy + oz * oz - self.radius * self.radius;
+
+        let discriminant = b * b - c;
+
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let sqrt_discriminant = discriminant.sqrt();
+        let near = -b - sqrt_discriminant;
+        let far = -b + sqrt_discriminant;
+
+        if far < 0.0 {
+            return None;
+        }
+
+        Some(if near >= 0.0 { near } else { far })
+    }
+}
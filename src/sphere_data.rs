@@ -21,6 +21,31 @@ impl From<Vec3A> for Vertex {
     }
 }
 
+impl From<Vec3> for Vertex {
+    fn from(vec: Vec3) -> Self {
+        Vertex {
+            position: [vec.x, vec.y, vec.z],
+        }
+    }
+}
+
+/// A set of indexed triangle geometry that [`SpheresPass`](crate::spheres_pass::SpheresPass)
+/// can draw one instance per entry of a `Sphere` buffer. `SphereData` is the
+/// built-in icosphere; [`MeshData`](crate::mesh_data::MeshData) implements this
+/// too, so a loaded model can stand in for it wherever geometry is expected.
+pub trait GeometryData {
+    fn vertices(&self) -> &Buffer<[Vertex], buffer::Usages<O, O, O, O, X, O, O, O, O, O>>;
+
+    fn indices(&self) -> &Buffer<[u32], buffer::Usages<O, O, O, O, O, X, O, O, O, O>>;
+
+    /// The radius of the geometry's bounding sphere in local space. `main`
+    /// passes this to `ComputeSphereBounds`/`CullSpheresPass` as
+    /// `geometry_scale`, which multiplies each instance's `Sphere::radius` so
+    /// geometry whose bounding sphere isn't unit radius still bounds and
+    /// culls conservatively.
+    fn bounding_radius(&self) -> f32;
+}
+
 pub struct SphereData {
     pub vertices: Buffer<[Vertex], buffer::Usages<O, O, O, O, X, O, O, O, O, O>>,
     pub indices: Buffer<[u32], buffer::Usages<O, O, O, O, O, X, O, O, O, O>>,
@@ -37,3 +62,17 @@ impl SphereData {
         SphereData { vertices, indices }
     }
 }
+
+impl GeometryData for SphereData {
+    fn vertices(&self) -> &Buffer<[Vertex], buffer::Usages<O, O, O, O, X, O, O, O, O, O>> {
+        &self.vertices
+    }
+
+    fn indices(&self) -> &Buffer<[u32], buffer::Usages<O, O, O, O, O, X, O, O, O, O>> {
+        &self.indices
+    }
+
+    fn bounding_radius(&self) -> f32 {
+        1.0
+    }
+}
@@ -0,0 +1,177 @@
+use std::f32::consts::PI;
+use std::ops::Range;
+
+use bytemuck::Zeroable;
+use empa::access_mode::ReadWrite;
+use empa::buffer::{Storage, StorageBinding, Uniform};
+use empa::command::{CommandEncoder, DispatchWorkgroups, ResourceBindingCommandEncoder};
+use empa::compute_pipeline::{
+    ComputePipeline, ComputePipelineDescriptorBuilder, ComputeStageBuilder,
+};
+use empa::device::Device;
+use empa::resource_binding::BindGroupLayout;
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::{abi, buffer};
+
+use crate::sphere::Sphere;
+use crate::velocity::Velocity;
+
+const GROUP_SIZE: u32 = 256;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+#[derive(abi::Sized, Clone, Copy, Debug, Zeroable)]
+struct Uniforms {
+    dt: f32,
+    gravity: f32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "COMPUTE")]
+    uniforms: Uniform<'a, Uniforms>,
+    #[resource(binding = 1, visibility = "COMPUTE")]
+    spheres: Storage<'a, [Sphere], ReadWrite>,
+    #[resource(binding = 2, visibility = "COMPUTE")]
+    velocities: Storage<'a, [Velocity], ReadWrite>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+pub struct SphereSimulationPassInput<'a, U0, U1> {
+    pub dt: f32,
+    pub gravity: f32,
+    pub spheres: buffer::View<'a, [Sphere], U0>,
+    pub velocities: buffer::View<'a, [Velocity], U1>,
+}
+
+pub struct SphereSimulationPass {
+    device: Device,
+    bind_group_layout: BindGroupLayout<ResourcesLayout>,
+    pipeline: ComputePipeline<(ResourcesLayout,)>,
+}
+
+impl SphereSimulationPass {
+    pub async fn init(device: Device) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_compute_pipeline(
+                &ComputePipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .compute(ComputeStageBuilder::begin(&shader, "main").finish())
+                    .finish(),
+            )
+            .await;
+
+        SphereSimulationPass {
+            device,
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    pub fn encode(
+        &self,
+        encoder: CommandEncoder,
+        input: SphereSimulationPassInput<impl StorageBinding, impl StorageBinding>,
+    ) -> CommandEncoder {
+        let SphereSimulationPassInput {
+            dt,
+            gravity,
+            spheres,
+            velocities,
+        } = input;
+
+        let uniforms = self
+            .device
+            .create_buffer(Uniforms { dt, gravity }, buffer::Usages::uniform_binding());
+
+        let workgroups = (spheres.len() as u32).div_ceil(GROUP_SIZE);
+
+        let bind_group = self.device.create_bind_group(
+            &self.bind_group_layout,
+            Resources {
+                uniforms: uniforms.uniform(),
+                spheres: spheres.storage(),
+                velocities: velocities.storage(),
+            },
+        );
+
+        encoder
+            .begin_compute_pass()
+            .set_pipeline(&self.pipeline)
+            .set_bind_groups(&bind_group)
+            .dispatch_workgroups(DispatchWorkgroups {
+                count_x: workgroups,
+                count_y: 1,
+                count_z: 1,
+            })
+            .end()
+    }
+}
+
+/// Describes an emitter-style spawn: spheres of a fixed `sphere_radius`
+/// scattered on a disk at a uniformly random angle and at a distance from
+/// its center drawn uniformly from `disk_radius` (so the spread is uniform in
+/// radius, not in area), each given an upward velocity sampled from
+/// `vertical_speed`.
+pub struct EmitterDescriptor {
+    pub count: usize,
+    pub sphere_radius: f32,
+    pub disk_radius: Range<f32>,
+    pub vertical_speed: Range<f32>,
+}
+
+/// Seeds an initial `(spheres, velocities)` pair for [`SphereSimulationPass`]
+/// from an [`EmitterDescriptor`], so callers get a live emitter-style spawn
+/// rather than having to hand-place every particle.
+pub fn init_from_distribution(descriptor: EmitterDescriptor) -> (Vec<Sphere>, Vec<Velocity>) {
+    let EmitterDescriptor {
+        count,
+        sphere_radius,
+        disk_radius,
+        vertical_speed,
+    } = descriptor;
+
+    let mut spheres = Vec::with_capacity(count);
+    let mut velocities = Vec::with_capacity(count);
+    let mut rng_state: u32 = 0x9e3779b9;
+
+    for _ in 0..count {
+        let theta = next_unit(&mut rng_state) * 2.0 * PI;
+        let radius = lerp(disk_radius.start, disk_radius.end, next_unit(&mut rng_state));
+        let speed = lerp(
+            vertical_speed.start,
+            vertical_speed.end,
+            next_unit(&mut rng_state),
+        );
+
+        spheres.push(Sphere {
+            origin: abi::Vec3(radius * theta.cos(), 0.0, radius * theta.sin()),
+            radius: sphere_radius,
+        });
+        velocities.push(Velocity {
+            linear: abi::Vec3(0.0, speed, 0.0),
+        });
+    }
+
+    (spheres, velocities)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// A small xorshift PRNG is enough to scatter an emitter's particles without
+// pulling in a dependency on a full-blown `rand` crate.
+fn next_unit(state: &mut u32) -> f32 {
+    *state ^= *state << 13;
+    *state ^= *state >> 17;
+    *state ^= *state << 5;
+
+    (*state as f32) / (u32::MAX as f32)
+}
@@ -1,7 +1,7 @@
 use empa::buffer::{Buffer, Storage, Uniform};
 use empa::command::{
-    DrawIndexed, DrawIndexedCommandEncoder, RenderBundleEncoderDescriptor, RenderStateEncoder,
-    ResourceBindingCommandEncoder,
+    DrawIndexedIndirect, DrawIndexedIndirectCommandEncoder, RenderBundleEncoderDescriptor,
+    RenderStateEncoder, ResourceBindingCommandEncoder,
 };
 use empa::device::Device;
 use empa::render_pipeline::{
@@ -10,13 +10,14 @@ use empa::render_pipeline::{
 };
 use empa::resource_binding::BindGroupLayout;
 use empa::shader_module::{shader_source, ShaderSource};
-use empa::texture::format::{depth24plus, rgba8unorm};
+use empa::texture::format::depth24plus;
 use empa::type_flag::{O, X};
 use empa::{abi, buffer, CompareFunction};
 
-use crate::renderer::{MainPassBundle, MainPassLayout};
+use crate::cull_spheres_pass::DrawIndexedIndirectArgs;
+use crate::renderer::{MainColorFormat, MainPassBundle, MainPassLayout, MAIN_COLOR_FORMAT};
 use crate::sphere::Sphere;
-use crate::sphere_data::{SphereData, Vertex};
+use crate::sphere_data::{GeometryData, Vertex};
 
 const SHADER: ShaderSource = shader_source!("shader.wgsl");
 
@@ -31,6 +32,8 @@ struct Resources<'a> {
     uniform_buffer: Uniform<'a, Uniforms>,
     #[resource(binding = 1, visibility = "VERTEX | FRAGMENT")]
     spheres: Storage<'a, [Sphere]>,
+    #[resource(binding = 2, visibility = "VERTEX | FRAGMENT")]
+    visible_indices: Storage<'a, [u32]>,
 }
 
 type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
@@ -62,7 +65,7 @@ impl SpheresPass {
                     .fragment(
                         FragmentStageBuilder::begin(&shader, "frag_main")
                             .color_outputs(ColorOutput {
-                                format: rgba8unorm,
+                                format: MAIN_COLOR_FORMAT,
                                 write_mask: ColorWrite::All,
                             })
                             .finish(),
@@ -90,14 +93,18 @@ impl SpheresPass {
         }
     }
 
-    pub fn render_bundle<U>(
+    pub fn render_bundle<U0, U1, U2>(
         &self,
         world_to_clip: abi::Mat4x4,
-        sphere_data: &SphereData,
-        spheres: buffer::View<[Sphere], U>,
+        geometry: &impl GeometryData,
+        spheres: buffer::View<[Sphere], U0>,
+        visible_indices: buffer::View<[u32], U1>,
+        indirect_args: buffer::View<DrawIndexedIndirectArgs, U2>,
     ) -> Option<MainPassBundle>
     where
-        U: buffer::StorageBinding,
+        U0: buffer::StorageBinding,
+        U1: buffer::StorageBinding,
+        U2: buffer::IndirectBinding,
     {
         if spheres.len() == 0 {
             return None;
@@ -112,25 +119,25 @@ impl SpheresPass {
             Resources {
                 uniform_buffer: self.uniforms.uniform(),
                 spheres: spheres.storage(),
+                visible_indices: visible_indices.storage(),
             },
         );
 
         let render_bundle_encoder = self.device.create_render_bundle_encoder(
-            &RenderBundleEncoderDescriptor::new::<rgba8unorm>()
+            &RenderBundleEncoderDescriptor::new::<MainColorFormat>()
                 .depth_stencil_format::<depth24plus>(),
         );
 
+        // The real instance count now lives in `indirect_args`, populated by
+        // `CullSpheresPass`'s compaction shader; the GPU decides how many
+        // instances to draw instead of a CPU-known `spheres.len()`.
         let bundle = render_bundle_encoder
             .set_pipeline(&self.pipeline)
-            .set_vertex_buffers(&sphere_data.vertices)
-            .set_index_buffer(&sphere_data.indices)
+            .set_vertex_buffers(geometry.vertices())
+            .set_index_buffer(geometry.indices())
             .set_bind_groups(&bind_group)
-            .draw_indexed(DrawIndexed {
-                index_count: sphere_data.indices.len() as u32,
-                instance_count: spheres.len() as u32,
-                first_index: 0,
-                base_vertex: 0,
-                first_instance: 0,
+            .draw_indexed_indirect(DrawIndexedIndirect {
+                indirect_buffer: indirect_args,
             })
             .finish();
 
@@ -0,0 +1,148 @@
+use empa::buffer;
+use empa::buffer::Uniform;
+use empa::command::{
+    Draw, DrawCommandEncoder, RenderBundle, RenderBundleEncoderDescriptor, RenderStateEncoder,
+    ResourceBindingCommandEncoder,
+};
+use empa::device::Device;
+use empa::render_pipeline::{
+    ColorOutput, ColorWrite, FragmentStageBuilder, PrimitiveAssembly,
+    RenderPipelineDescriptorBuilder, VertexStageBuilder,
+};
+use empa::render_target::RenderLayout;
+use empa::resource_binding::{Sampler, Texture2D as SampledTexture2D};
+use empa::sampler::{FilterMode, SamplerDescriptor};
+use empa::shader_module::{shader_source, ShaderSource};
+use empa::texture::format::rgba8unorm;
+use empa::texture::{SampledTextureDescriptor, Texture2D};
+use empa::type_flag::{O, X};
+use empa::texture;
+
+use crate::renderer::MainColorFormat;
+
+const SHADER: ShaderSource = shader_source!("shader.wgsl");
+
+pub type TonemapLayout = RenderLayout<rgba8unorm, ()>;
+pub type TonemapBundle = RenderBundle<TonemapLayout>;
+
+/// Operator used to map HDR radiance values down into the `0..1` range
+/// before the final `rgba8unorm` write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TonemapOperator {
+    Reinhard,
+    Aces,
+}
+
+impl TonemapOperator {
+    fn as_u32(&self) -> u32 {
+        match self {
+            TonemapOperator::Reinhard => 0,
+            TonemapOperator::Aces => 1,
+        }
+    }
+}
+
+#[derive(empa::abi::Sized, Clone, Copy, Debug)]
+struct Uniforms {
+    exposure: f32,
+    operator: u32,
+}
+
+#[derive(empa::resource_binding::Resources)]
+struct Resources<'a> {
+    #[resource(binding = 0, visibility = "FRAGMENT")]
+    uniforms: Uniform<'a, Uniforms>,
+    #[resource(binding = 1, visibility = "FRAGMENT")]
+    hdr_sampler: Sampler<'a>,
+    #[resource(binding = 2, visibility = "FRAGMENT")]
+    hdr_texture: SampledTexture2D<'a>,
+}
+
+type ResourcesLayout = <Resources<'static> as empa::resource_binding::Resources>::Layout;
+
+pub struct TonemapPass {
+    render_bundle: TonemapBundle,
+}
+
+impl TonemapPass {
+    pub async fn init(
+        device: Device,
+        hdr_texture: &Texture2D<MainColorFormat, texture::Usages<X, X, O, O, O>>,
+        exposure: f32,
+        operator: TonemapOperator,
+    ) -> Self {
+        let shader = device.create_shader_module(&SHADER);
+
+        let bind_group_layout = device.create_bind_group_layout::<ResourcesLayout>();
+        let pipeline_layout = device.create_pipeline_layout(&bind_group_layout);
+
+        let pipeline = device
+            .create_render_pipeline(
+                &RenderPipelineDescriptorBuilder::begin()
+                    .layout(&pipeline_layout)
+                    .primitive_assembly(PrimitiveAssembly::triangle_list())
+                    .vertex(
+                        VertexStageBuilder::begin(&shader, "vert_main")
+                            .vertex_layout::<()>()
+                            .finish(),
+                    )
+                    .fragment(
+                        FragmentStageBuilder::begin(&shader, "frag_main")
+                            .color_outputs(ColorOutput {
+                                format: rgba8unorm,
+                                write_mask: ColorWrite::All,
+                            })
+                            .finish(),
+                    )
+                    .finish(),
+            )
+            .await;
+
+        let uniforms = device.create_buffer(
+            Uniforms {
+                exposure,
+                operator: operator.as_u32(),
+            },
+            buffer::Usages::uniform_binding().and_copy_dst(),
+        );
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let bind_group = device.create_bind_group(
+            &bind_group_layout,
+            Resources {
+                uniforms: uniforms.uniform(),
+                hdr_sampler: sampler.sampled(),
+                hdr_texture: hdr_texture.sampled_image(&SampledTextureDescriptor::default()),
+            },
+        );
+
+        // A fullscreen triangle needs no vertex or index buffer; the vertex
+        // shader derives its positions from `vertex_index`.
+        let render_bundle_encoder =
+            device.create_render_bundle_encoder(&RenderBundleEncoderDescriptor::new::<rgba8unorm>());
+
+        let render_bundle = render_bundle_encoder
+            .set_pipeline(&pipeline)
+            .set_bind_groups(&bind_group)
+            .draw(Draw {
+                vertex_count: 3,
+                instance_count: 1,
+                first_vertex: 0,
+                first_instance: 0,
+            })
+            .finish();
+
+        TonemapPass { render_bundle }
+    }
+
+    /// Returns the pre-recorded render bundle that samples the HDR texture
+    /// and writes the tone mapped result into a `rgba8unorm` color target.
+    pub fn render_bundle(&self) -> &TonemapBundle {
+        &self.render_bundle
+    }
+}
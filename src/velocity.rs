@@ -0,0 +1,8 @@
+use bytemuck::Zeroable;
+use empa::abi;
+
+#[derive(abi::Sized, Clone, Copy, PartialEq, Debug, Zeroable)]
+#[repr(C)]
+pub struct Velocity {
+    pub linear: abi::Vec3<f32>,
+}